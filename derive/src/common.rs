@@ -20,9 +20,23 @@ pub struct Codable {
     #[darling(rename = "untagged")]
     pub untagged: Flag,
 
+    /// For an enum, the map key under which the variant discriminant is stored (paired with
+    /// `content`, or alone for internal tagging -- see [`Self::content`]).
+    ///
+    /// For a struct, an integer CBOR semantic tag (major type 6) number: the whole struct is
+    /// wrapped as `Value::Tag(N, Box::new(<the struct's usual map/array>))` on encode, and that
+    /// wrapping is checked and stripped on decode. A field can be tagged the same way with
+    /// `#[cbor(tag = N)]` on the field itself -- see [`Field::tag`].
     #[darling(rename = "tag")]
     pub tag: Option<Key>,
 
+    /// Paired with `tag` to select serde's adjacently-tagged representation: the enum encodes as
+    /// `{ <tag>: <variant-key>, <content>: <variant-body> }` instead of either the externally
+    /// tagged `{ <variant-key>: <body> }` wrapper (plain `tag`-less enums) or the internally
+    /// tagged merge-in-place (`tag` alone).
+    #[darling(rename = "content")]
+    pub content: Option<Key>,
+
     #[darling(rename = "as_array")]
     pub as_array: Flag,
 
@@ -34,6 +48,18 @@ pub struct Codable {
 
     #[darling(rename = "allow_unknown")]
     pub allow_unknown: Flag,
+
+    /// Case-conversion rule applied to every field's (for structs) or variant's (for enums) CBOR
+    /// key, unless that field/variant has an explicit `rename`.
+    #[darling(rename = "rename_all")]
+    pub rename_all: Option<RenameRule>,
+
+    /// Runs every encoded `Value` for this type through `oasis_cbor::canonical::canonicalize`
+    /// before returning it, so plain `to_vec`/`to_value` already produce RFC 8949 §4.2.1
+    /// deterministic map-key ordering for this type without callers having to reach for
+    /// `to_vec_canonical`/`to_value_canonical` themselves.
+    #[darling(rename = "deterministic")]
+    pub deterministic: Flag,
 }
 
 impl Codable {
@@ -48,6 +74,26 @@ impl Codable {
                 .with_span(&self.untagged));
         }
 
+        if self.content.is_some() && self.tag.is_none() {
+            return Err(Error::custom("content requires tag to also be set")
+                .with_span(&self.content));
+        }
+
+        if self.untagged.is_present() && self.content.is_some() {
+            return Err(Error::custom("Cannot set untagged and content")
+                .with_span(&self.untagged));
+        }
+
+        if let darling::ast::Data::Struct(_) = &self.data {
+            if let Some(tag) = &self.tag {
+                if tag.as_u64().is_none() {
+                    return Err(Error::custom(
+                        "a struct's tag must be an integer CBOR tag number",
+                    ));
+                }
+            }
+        }
+
         Ok(self)
     }
 }
@@ -75,6 +121,15 @@ impl Key {
             Key::Integer(ref v) => v.into_cbor_value(),
         }
     }
+
+    /// The key as a plain integer, for uses (like a CBOR semantic tag number) where a string
+    /// doesn't make sense.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Key::Integer(v) => Some(*v),
+            Key::String(_) => None,
+        }
+    }
 }
 
 impl darling::FromMeta for Key {
@@ -92,8 +147,147 @@ impl darling::FromMeta for Key {
     }
 }
 
+/// A `rename_all` case-conversion rule, mirroring `serde_derive`'s `internals/case.rs`.
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    LowerCase,
+    UpperCase,
+    PascalCase,
+    CamelCase,
+    SnakeCase,
+    ScreamingSnakeCase,
+    KebabCase,
+    ScreamingKebabCase,
+}
+
+impl darling::FromMeta for RenameRule {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(match value {
+            "lowercase" => Self::LowerCase,
+            "UPPERCASE" => Self::UpperCase,
+            "PascalCase" => Self::PascalCase,
+            "camelCase" => Self::CamelCase,
+            "snake_case" => Self::SnakeCase,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnakeCase,
+            "kebab-case" => Self::KebabCase,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebabCase,
+            _ => {
+                return Err(darling::Error::unknown_value(value));
+            }
+        })
+    }
+}
+
+impl RenameRule {
+    /// Renames a struct field identifier, which is assumed to already be snake_case.
+    pub fn apply_to_field(&self, ident: &str) -> String {
+        self.join(&Self::split_words(ident, '_'))
+    }
+
+    /// Renames an enum variant identifier, which is assumed to already be PascalCase.
+    pub fn apply_to_variant(&self, ident: &str) -> String {
+        self.join(&Self::split_pascal_case_words(ident))
+    }
+
+    /// Splits `ident` on `separator`, e.g. snake_case on `_`.
+    fn split_words(ident: &str, separator: char) -> Vec<String> {
+        ident
+            .split(separator)
+            .filter(|w| !w.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+
+    /// Splits a PascalCase identifier into words, at each uppercase letter that follows a
+    /// lowercase letter.
+    fn split_pascal_case_words(ident: &str) -> Vec<String> {
+        let mut words = vec![];
+        let mut current = String::new();
+        let mut prev_was_lowercase = false;
+        for c in ident.chars() {
+            if c.is_uppercase() && prev_was_lowercase && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_was_lowercase = c.is_lowercase();
+            current.push(c);
+        }
+        if !current.is_empty() {
+            words.push(current);
+        }
+        words
+    }
+
+    fn join(&self, words: &[String]) -> String {
+        match self {
+            Self::LowerCase => words.iter().map(|w| w.to_lowercase()).collect(),
+            Self::UpperCase => words.iter().map(|w| w.to_uppercase()).collect(),
+            Self::PascalCase => words.iter().map(|w| Self::capitalize(w)).collect(),
+            Self::CamelCase => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| {
+                    if i == 0 {
+                        w.to_lowercase()
+                    } else {
+                        Self::capitalize(w)
+                    }
+                })
+                .collect(),
+            Self::SnakeCase => words
+                .iter()
+                .map(|w| w.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            Self::ScreamingSnakeCase => words
+                .iter()
+                .map(|w| w.to_uppercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            Self::KebabCase => words
+                .iter()
+                .map(|w| w.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("-"),
+            Self::ScreamingKebabCase => words
+                .iter()
+                .map(|w| w.to_uppercase())
+                .collect::<Vec<_>>()
+                .join("-"),
+        }
+    }
+
+    fn capitalize(word: &str) -> String {
+        let mut chars = word.chars();
+        match chars.next() {
+            None => String::new(),
+            Some(c) => c.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        }
+    }
+}
+
+/// The fallback a missing `#[cbor(optional)]` field uses instead of decoding a null value: either
+/// a bare `#[cbor(default)]`, which calls `Default::default()`, or `#[cbor(default = "path")]`,
+/// which calls the given zero-argument function. Mirrors serde_derive's `default` attribute.
+pub enum FieldDefault {
+    Default,
+    Path(Path),
+}
+
+impl darling::FromMeta for FieldDefault {
+    fn from_word() -> Result<Self> {
+        Ok(Self::Default)
+    }
+
+    fn from_string(value: &str) -> Result<Self> {
+        syn::parse_str(value)
+            .map(Self::Path)
+            .map_err(|e| Error::custom(e.to_string()))
+    }
+}
+
 #[derive(FromField)]
 #[darling(attributes(cbor))]
+#[darling(and_then = "Self::validate")]
 pub struct Field {
     pub ident: Option<Ident>,
     pub ty: Type,
@@ -110,34 +304,125 @@ pub struct Field {
     #[darling(rename = "skip_serializing_if")]
     pub skip_serializing_if: Option<Path>,
 
+    /// Calls the given function with a reference to this field's value instead of its own
+    /// `Encode::into_cbor_value`, so it can produce some other `Encode`-able value to stand in
+    /// as this field's encoded representation. Paired with `deserialize_with`.
     #[darling(rename = "serialize_with")]
     pub serialize_with: Option<Path>,
 
+    /// Calls the given function with this field's raw decoded `Value` instead of its own
+    /// `Decode::try_from_cbor_value`, so it can fully control how the on-wire representation
+    /// maps back to this field's type. Paired with `serialize_with`.
     #[darling(rename = "deserialize_with")]
     pub deserialize_with: Option<Path>,
+
+    /// Fallback used in place of decoding a null value when an `optional` field's key is absent
+    /// from the map. See [`FieldDefault`].
+    #[darling(rename = "default")]
+    pub default: Option<FieldDefault>,
+
+    /// Additional CBOR keys that also match this field on decode, tried in declaration order once
+    /// the primary key (`rename`, or the field's own name) is not found. Lets a struct keep
+    /// decoding payloads produced under an older or alternative schema while still encoding under
+    /// the canonical key. Mirrors serde's repeatable `#[serde(alias = "...")]`.
+    #[darling(rename = "alias", multiple)]
+    pub alias: Vec<Key>,
+
+    /// Path to a module exposing `encode(&T) -> Value` and `decode(Value) -> Result<T,
+    /// DecodeError>`, used instead of the field's own `Encode`/`Decode` impl. This is how a
+    /// plain `Vec<u8>`/`[u8; N]` field opts into byte-string encoding without relying on the
+    /// crate's `min_specialization`-based blanket impls (see `#[cbor(with = "oasis_cbor::bytes")]`).
+    #[darling(rename = "with")]
+    pub with: Option<Path>,
+
+    /// Merges this field's own map entries into the enclosing struct's map instead of nesting
+    /// them under the field's own key, like serde's `#[serde(flatten)]`. The field's type must
+    /// implement `EncodeAsMap` for encoding; on decode, it receives every map entry the struct's
+    /// other fields didn't claim. At most one field per struct may be flattened.
+    #[darling(rename = "flatten")]
+    pub flatten: Flag,
+
+    /// Wraps this field's encoded value in a CBOR semantic tag (major type 6): `Value::Tag(N,
+    /// Box::new(<the field's usual value>))` on encode, checked and unwrapped on decode. Mirrors
+    /// the container-level `#[cbor(tag = N)]` on [`Codable`], but scoped to a single field rather
+    /// than the whole struct.
+    #[darling(rename = "tag")]
+    pub tag: Option<u64>,
 }
 
 impl Field {
-    pub fn to_cbor_key_expr(&self) -> TokenStream {
+    fn validate(self) -> Result<Self> {
+        if self.default.is_some() && !self.optional.is_present() {
+            return Err(Error::custom("default requires optional to also be set"));
+        }
+
+        if self.flatten.is_present() {
+            if self.rename.is_some() {
+                return Err(Error::custom("cannot use rename and flatten together"));
+            }
+            if !self.alias.is_empty() {
+                return Err(Error::custom("cannot use alias and flatten together"));
+            }
+            if self.optional.is_present() {
+                return Err(Error::custom("cannot use optional and flatten together"));
+            }
+            if self.with.is_some() {
+                return Err(Error::custom("cannot use with and flatten together"));
+            }
+            if self.tag.is_some() {
+                return Err(Error::custom("cannot use tag and flatten together"));
+            }
+        }
+
+        if self.tag.is_some() && self.optional.is_present() {
+            return Err(Error::custom("cannot use tag and optional together"));
+        }
+
+        Ok(self)
+    }
+
+    /// The expression used to fill in an `optional` field whose key was absent, given its
+    /// `default` setting.
+    pub fn default_expr(&self) -> TokenStream {
+        match &self.default {
+            Some(FieldDefault::Path(path)) => quote!(Ok(#path())),
+            Some(FieldDefault::Default) => quote!(Ok(::std::default::Default::default())),
+            None => quote!(__cbor::Decode::try_from_cbor_value(__cbor::Value::Simple(
+                __cbor::SimpleValue::NullValue
+            ))),
+        }
+    }
+
+    /// Expressions for each `#[cbor(alias)]` key, in declaration order.
+    pub fn alias_key_exprs(&self) -> impl Iterator<Item = TokenStream> + '_ {
+        self.alias.iter().map(Key::to_cbor_key_expr)
+    }
+
+    pub fn to_cbor_key_expr(&self, rename_all: Option<&RenameRule>) -> TokenStream {
         self.rename
             .as_ref()
             .map(Key::to_cbor_key_expr)
             .unwrap_or_else(|| {
-                // No explicit rename, use identifier name.
-                let ident = self.ident.as_ref().unwrap().to_string();
+                let ident = self.cbor_key_string(rename_all);
                 quote!( __cbor::values::IntoCborValue::into_cbor_value(#ident) )
             })
     }
 
-    pub fn to_cbor_key(&self) -> oasis_cbor_value::Value {
+    pub fn to_cbor_key(&self, rename_all: Option<&RenameRule>) -> oasis_cbor_value::Value {
         self.rename
             .as_ref()
             .map(Key::to_cbor_key)
-            .unwrap_or_else(|| {
-                // No explicit rename, use identifier name.
-                let ident = self.ident.as_ref().unwrap().to_string();
-                ident.into_cbor_value()
-            })
+            .unwrap_or_else(|| self.cbor_key_string(rename_all).into_cbor_value())
+    }
+
+    /// The field's CBOR key, absent an explicit `rename`: its identifier, passed through
+    /// `rename_all` if the container set one.
+    fn cbor_key_string(&self, rename_all: Option<&RenameRule>) -> String {
+        let ident = self.ident.as_ref().unwrap().to_string();
+        match rename_all {
+            Some(rule) => rule.apply_to_field(&ident),
+            None => ident,
+        }
     }
 }
 
@@ -168,16 +453,25 @@ pub struct Variant {
 
     #[darling(rename = "missing")]
     pub missing: Flag,
+
+    /// Case-conversion rule applied to this variant's own fields (for struct-like variants),
+    /// unless a field has an explicit `rename`. Distinct from the container-level `rename_all`
+    /// on [`Codable`], which renames the variant identifiers themselves.
+    #[darling(rename = "rename_all")]
+    pub rename_all: Option<RenameRule>,
 }
 
 impl Variant {
-    pub fn to_cbor_key_expr(&self) -> TokenStream {
+    pub fn to_cbor_key_expr(&self, rename_all: Option<&RenameRule>) -> TokenStream {
         self.rename
             .as_ref()
             .map(Key::to_cbor_key_expr)
             .unwrap_or_else(|| {
-                // No explicit rename, use identifier name.
                 let ident = self.ident.to_string();
+                let ident = match rename_all {
+                    Some(rule) => rule.apply_to_variant(&ident),
+                    None => ident,
+                };
                 quote!( __cbor::values::IntoCborValue::into_cbor_value(#ident) )
             })
     }
@@ -4,7 +4,7 @@ use quote::{quote, quote_spanned};
 use syn::{spanned::Spanned, DeriveInput, Ident, Index, Member};
 
 use crate::{
-    common::{Codable, Field, Variant},
+    common::{Codable, Field, Key, RenameRule, Variant},
     util,
 };
 
@@ -25,7 +25,9 @@ pub fn derive(input: DeriveInput) -> TokenStream {
                 dec.as_array.is_some(),
                 fields,
                 quote!(Self),
+                dec.rename_all.as_ref(),
             );
+            let inner = unwrap_tag(dec.tag.as_ref(), inner);
             quote!(Ok({ #inner }))
         }
     };
@@ -45,12 +47,57 @@ pub fn derive(input: DeriveInput) -> TokenStream {
     })
 }
 
+/// Checks and strips a struct's container-level `#[cbor(tag = N)]` wrapping (see
+/// [`Codable::tag`]'s struct-side meaning) before handing `value` off to `inner`. A no-op if
+/// `tag` is absent.
+fn unwrap_tag(tag: Option<&Key>, inner: TokenStream) -> TokenStream {
+    let tag_number = match tag.map(Key::as_u64) {
+        None => return inner,
+        Some(n) => n.expect("validated to be an integer"),
+    };
+
+    quote! {
+        let value = match value {
+            __cbor::Value::Tag(tag, inner) if tag == #tag_number => *inner,
+            __cbor::Value::Tag(_, _) => return Err(__cbor::DecodeError::UnexpectedTag),
+            _ => return Err(__cbor::DecodeError::UnexpectedType),
+        };
+
+        #inner
+    }
+}
+
+/// The callable a field's decode site applies to its raw `Value`, honoring `with`/
+/// `deserialize_with` (in that precedence order) before falling back to the field type's own
+/// `Decode` impl. Mirrors `encode.rs`'s analogous `field_value` construction for `with`/
+/// `serialize_with`.
+///
+/// For `deserialize_with = "path::fn"`, `path::fn` is called with an intermediate value decoded
+/// via that value's own `Decode` impl -- its type is left for Rust to infer from `path::fn`'s own
+/// parameter type, the same way `serialize_with`'s function produces an intermediate value for
+/// `Encode` to pick up on the way out.
+fn decode_fn_expr(
+    field_ty: &syn::Type,
+    with: &Option<syn::Path>,
+    deserialize_with: &Option<syn::Path>,
+) -> TokenStream {
+    match (with, deserialize_with) {
+        (Some(with_mod), _) => quote_spanned!(field_ty.span()=> #with_mod::decode),
+        (None, Some(custom_decode_fn)) => quote_spanned!(field_ty.span()=> |v: __cbor::Value| {
+            let decoded = __cbor::Decode::try_from_cbor_value(v)?;
+            #custom_decode_fn(decoded)
+        }),
+        (None, None) => quote_spanned!(field_ty.span()=> __cbor::Decode::try_from_cbor_value),
+    }
+}
+
 fn derive_struct(
     ident: &Ident,
     transparent: bool,
     as_array: bool,
     fields: darling::ast::Fields<&Field>,
     self_ty: TokenStream,
+    rename_all: Option<&RenameRule>,
 ) -> TokenStream {
     if transparent {
         // Transparently forward the implementation to the underlying type. This is only valid for
@@ -61,7 +108,7 @@ fn derive_struct(
         // Process all fields and decode the structure as a map or array.
         let as_array = fields.is_tuple() || fields.is_newtype() || as_array;
 
-        let (extract_value, field_map_items): (_, Vec<_>) = if as_array {
+        let (setup, field_map_items, finish): (_, Vec<_>, _) = if as_array {
             // Fields represented as an array.
             let extract_value = quote! {
                 match value {
@@ -87,16 +134,42 @@ fn derive_struct(
                         // If the field should be skipped, always use Default::default() as value.
                         quote_spanned!(field_ty.span()=> ::std::default::Default::default())
                     } else {
-                        let decode_fn =
-                            quote_spanned!(field_ty.span()=> __cbor::Decode::try_from_cbor_value);
-                        quote!(#decode_fn(it.next().ok_or(__cbor::DecodeError::MissingField)?)?)
+                        let decode_fn = decode_fn_expr(field_ty, &field.with, &field.deserialize_with);
+                        let field_name = i.to_string();
+
+                        let value_expr = quote!(it.next().ok_or(__cbor::DecodeError::MissingField)?);
+                        let value_expr = match field.tag {
+                            Some(tag_number) => quote! {
+                                match #value_expr {
+                                    __cbor::Value::Tag(tag, inner) if tag == #tag_number => *inner,
+                                    __cbor::Value::Tag(_, _) => return Err(__cbor::DecodeError::UnexpectedTag),
+                                    _ => return Err(__cbor::DecodeError::UnexpectedType),
+                                }
+                            },
+                            None => value_expr,
+                        };
+
+                        quote! {
+                            #decode_fn(#value_expr)
+                                .map_err(|e| e.context(#field_name))?
+                        }
                     };
 
                     quote! { #field_ident: #field_value }
                 })
                 .collect();
 
-            (extract_value, field_map_items)
+            let setup = quote! {
+                let fields = #extract_value;
+                let mut it = fields.into_iter().peekable();
+            };
+            let finish = quote! {
+                if it.next().is_some() {
+                    return Err(__cbor::DecodeError::UnknownField);
+                }
+            };
+
+            (setup, field_map_items, finish)
         } else {
             // Field represented as a map.
             let extract_value = quote! {
@@ -106,43 +179,105 @@ fn derive_struct(
                 }
             };
 
-            // Sort fields by their CBOR keys to make destructure_cbor_map_peek_value_strict work.
-            let mut fields = fields.fields;
-            fields.sort_by(|a, b| a.to_cbor_key().partial_cmp(&b.to_cbor_key()).unwrap());
+            // A flattened field has no CBOR key of its own, so it's pulled out before the rest are
+            // sorted and matched by key; it instead receives whatever entries are left over once
+            // every other field has taken its share.
+            let (flatten_fields, mut fields): (Vec<_>, Vec<_>) = fields
+                .fields
+                .into_iter()
+                .partition(|field| field.flatten.is_present());
+
+            if flatten_fields.len() > 1 {
+                flatten_fields[1]
+                    .ident
+                    .span()
+                    .unwrap()
+                    .error("at most one field can be flattened".to_string())
+                    .emit();
+                return quote!({});
+            }
 
-            let field_map_items = fields
+            // Sort fields by their CBOR keys: the map entries are in canonical (sorted) order, and
+            // destructure_cbor_map_peek_value_strict consumes `it` front-to-back expecting each
+            // field's primary key to come up in the same order.
+            fields.sort_by(|a, b| {
+                a.to_cbor_key(rename_all)
+                    .partial_cmp(&b.to_cbor_key(rename_all))
+                    .unwrap()
+            });
+
+            let mut field_map_items: Vec<_> = fields
                 .iter()
                 .map(|field| {
                     let field_ident = field.ident.as_ref().unwrap();
                     let field_ty = &field.ty;
-                    let key = field.to_cbor_key_expr();
+                    let key = field.to_cbor_key_expr(rename_all);
 
                     let field_value = if field.skip.is_some() {
                         // If the field should be skipped, always use Default::default() as value.
                         quote_spanned!(field_ty.span()=> ::std::default::Default::default())
                     } else {
+                        let field_name_for_missing = field_ident.to_string();
                         let handle_missing_value = if field.optional.is_some() {
-                            let default = if field.default.is_some() {
-                                // Use the default value in case the value is not there.
-                                quote!(Ok(::std::default::Default::default()))
-                            } else {
-                                // Attempt decoding with null value.
-                                quote!(__cbor::Decode::try_from_cbor_value(__cbor::Value::Simple(
-                                    __cbor::SimpleValue::NullValue
-                                )))
-                            };
+                            // Use the configured default in case the value is not there, falling
+                            // back to decoding a null value if no default was given.
+                            let default = field.default_expr();
 
                             quote!( unwrap_or_else(|| #default) )
                         } else {
-                            // Value is not optional, so it must be there.
-                            quote!(ok_or(__cbor::DecodeError::MissingField)?)
+                            // Value is not optional, so it must be there. Attach the field name
+                            // here too, same as every other field-level failure below, so a
+                            // missing nested field reports `foo.bar: missing field` instead of a
+                            // bare `missing field` with no path.
+                            quote!(ok_or_else(|| __cbor::DecodeError::MissingField.context(#field_name_for_missing))?)
+                        };
+
+                        // A field with aliases can't use the strict peek: an alias key need not
+                        // sort adjacent to the field's primary key, so a front entry that sorts
+                        // before the primary key isn't necessarily unknown -- it might still be
+                        // this field arriving under one of its aliases. Defer that judgment to the
+                        // alias scan (and ultimately the final unknown-field check) instead of
+                        // hard-erroring here.
+                        //
+                        // With a flattened field present, the entries it will claim can sort
+                        // anywhere among this struct's own keys, so every key is scanned for.
+                        let peek_expr = if !flatten_fields.is_empty() {
+                            quote_spanned!(field_ty.span()=>
+                                __cbor::macros::destructure_cbor_map_alias_value(&mut it, #key))
+                        } else if field.alias.is_empty() {
+                            quote_spanned!(field_ty.span()=>
+                                __cbor::macros::destructure_cbor_map_peek_value_strict(&mut it, #key)?)
+                        } else {
+                            quote_spanned!(field_ty.span()=>
+                                __cbor::macros::destructure_cbor_map_peek_value(&mut it, #key))
+                        };
+                        let decode_fn = decode_fn_expr(field_ty, &field.with, &field.deserialize_with);
+                        let field_name = field_ident.to_string();
+
+                        // Once the primary key misses, probe each alias key in turn: unlike the
+                        // primary lookup, an alias need not sort adjacent to the field's own key.
+                        let alias_probes = field.alias_key_exprs().map(|alias_key| {
+                            quote!(let v = v.or_else(|| __cbor::macros::destructure_cbor_map_alias_value(&mut it, #alias_key));)
+                        });
+
+                        // If the field is tagged, check and strip the semantic-tag wrapper before
+                        // handing the inner value off to its own decode.
+                        let decode_value = match field.tag {
+                            Some(tag_number) => quote! {
+                                match v {
+                                    __cbor::Value::Tag(tag, inner) if tag == #tag_number => #decode_fn(*inner),
+                                    __cbor::Value::Tag(_, _) => Err(__cbor::DecodeError::UnexpectedTag),
+                                    _ => Err(__cbor::DecodeError::UnexpectedType),
+                                }
+                            },
+                            None => quote!(#decode_fn(v)),
                         };
 
-                        let destruct_fn = quote_spanned!(field_ty.span()=>
-                            __cbor::macros::destructure_cbor_map_peek_value_strict);
                         let field_value = quote!({
-                            let v: Option<__cbor::Value> = #destruct_fn(&mut it, #key)?;
-                            v.map(__cbor::Decode::try_from_cbor_value).#handle_missing_value?
+                            let v: Option<__cbor::Value> = #peek_expr;
+                            #(#alias_probes)*
+                            v.map(|v| #decode_value.map_err(|e| e.context(#field_name)))
+                                .#handle_missing_value?
                         });
 
                         field_value
@@ -152,20 +287,44 @@ fn derive_struct(
                 })
                 .collect();
 
-            (extract_value, field_map_items)
+            let setup = quote!(let mut it = #extract_value;);
+
+            // With no flatten field, every remaining entry is genuinely unrecognized. With one,
+            // it takes the entire residual map instead, so there's nothing left for the parent to
+            // reject -- the flattened type's own decode is responsible for its leftovers.
+            let finish = match flatten_fields.first() {
+                None => quote! {
+                    if !it.is_empty() {
+                        return Err(__cbor::DecodeError::UnknownField);
+                    }
+                },
+                Some(_) => quote!(),
+            };
+
+            if let Some(flatten_field) = flatten_fields.first() {
+                let flatten_ident = flatten_field.ident.as_ref().unwrap();
+                let flatten_ty = &flatten_field.ty;
+                let decode_fn =
+                    quote_spanned!(flatten_ty.span()=> __cbor::Decode::try_from_cbor_value);
+                let field_name = flatten_ident.to_string();
+
+                field_map_items.push(quote! {
+                    #flatten_ident: #decode_fn(__cbor::Value::Map(::std::mem::take(&mut it)))
+                        .map_err(|e| e.context(#field_name))?
+                });
+            }
+
+            (setup, field_map_items, finish)
         };
 
         quote! {
-            let fields = #extract_value;
-            let mut it = fields.into_iter().peekable();
+            #setup
 
             let v = #self_ty {
                 #(#field_map_items),*
             };
 
-            if it.next().is_some() {
-                return Err(__cbor::DecodeError::UnknownField);
-            }
+            #finish
 
             v
         }
@@ -183,14 +342,12 @@ fn derive_enum(dec: &Codable, variants: Vec<&Variant>) -> TokenStream {
         return quote!({});
     }
 
-    // Make sure decoding of untagged enums is not supported.
     if dec.untagged.is_some() {
-        dec.ident
-            .span()
-            .unwrap()
-            .error("cannot derive decoder for untagged enum".to_string())
-            .emit();
-        return quote!({});
+        return derive_enum_untagged(dec, &variants);
+    }
+
+    if dec.content.is_some() {
+        return derive_enum_adjacently_tagged(dec, &variants);
     }
 
     if variants.is_empty() {
@@ -212,7 +369,7 @@ fn derive_enum(dec: &Codable, variants: Vec<&Variant>) -> TokenStream {
                     quote_spanned!(variant.ident.span()=> __cbor::Encode::into_cbor_value);
                 quote!(#encoder_fn(#expr))
             }
-            None => variant.to_cbor_key_expr(),
+            None => variant.to_cbor_key_expr(dec.rename_all.as_ref()),
         };
 
         let variant_ident = &variant.ident;
@@ -236,7 +393,7 @@ fn derive_enum(dec: &Codable, variants: Vec<&Variant>) -> TokenStream {
             }
 
             let variant_ident = &variant.ident;
-            let key = variant.to_cbor_key_expr();
+            let key = variant.to_cbor_key_expr(dec.rename_all.as_ref());
 
             let decoder = if variant.fields.is_newtype() {
                 // Newtype variants map the key directly to the inner value as if transparent was used.
@@ -251,6 +408,7 @@ fn derive_enum(dec: &Codable, variants: Vec<&Variant>) -> TokenStream {
                     variant.as_array.is_some(),
                     variant.fields.as_ref(),
                     quote!(Self::#variant_ident),
+                    variant.rename_all.as_ref(),
                 );
                 quote!({ #inner })
             };
@@ -293,3 +451,156 @@ fn derive_enum(dec: &Codable, variants: Vec<&Variant>) -> TokenStream {
         }
     }
 }
+
+/// Derives the decoder for an `#[cbor(untagged)]` enum. Unlike the tagged case, there is no key
+/// to dispatch on, so each non-unit variant is attempted in declaration order against a clone of
+/// the value, and the first one that decodes without error wins (matching serde's untagged
+/// semantics). Unit variants are attempted last, by comparing against their encoded key/
+/// discriminant directly, since as bare values they would otherwise be indistinguishable from
+/// e.g. a newtype variant wrapping the same value.
+fn derive_enum_untagged(dec: &Codable, variants: &[&Variant]) -> TokenStream {
+    let non_unit_attempts = variants.iter().filter_map(|variant| {
+        if variant.skip.is_some() {
+            return None;
+        }
+        if variant.fields.is_unit() && !variant.as_struct.is_some() {
+            return None;
+        }
+
+        let variant_ident = &variant.ident;
+
+        let attempt = if variant.fields.is_newtype() {
+            let decode_fn =
+                quote_spanned!(variant.ident.span()=> __cbor::Decode::try_from_cbor_value);
+            quote!(#decode_fn(value.clone()).map(Self::#variant_ident))
+        } else {
+            let inner = derive_struct(
+                &variant.ident,
+                false,
+                variant.as_array.is_some(),
+                variant.fields.as_ref(),
+                quote!(Self::#variant_ident),
+                variant.rename_all.as_ref(),
+            );
+
+            quote!((|| -> ::std::result::Result<Self, __cbor::DecodeError> {
+                let value = value.clone();
+                Ok({ #inner })
+            })())
+        };
+
+        Some(quote! {
+            if let Ok(v) = #attempt {
+                return Ok(v);
+            }
+        })
+    });
+
+    let unit_attempts = variants.iter().filter_map(|variant| {
+        if variant.skip.is_some() {
+            return None;
+        }
+        if !variant.fields.is_unit() || variant.as_struct.is_some() {
+            return None;
+        }
+
+        let discriminant = match variant.discriminant {
+            Some(ref expr) => {
+                let encoder_fn =
+                    quote_spanned!(variant.ident.span()=> __cbor::Encode::into_cbor_value);
+                quote!(#encoder_fn(#expr))
+            }
+            None => variant.to_cbor_key_expr(dec.rename_all.as_ref()),
+        };
+
+        let variant_ident = &variant.ident;
+
+        Some(quote! {
+            if value == #discriminant {
+                return Ok(Self::#variant_ident);
+            }
+        })
+    });
+
+    quote! {
+        #(#non_unit_attempts)*
+        #(#unit_attempts)*
+
+        Err(__cbor::DecodeError::UnexpectedType)
+    }
+}
+
+/// Derives the decoder for a `#[cbor(tag = "...", content = "...")]` adjacently tagged enum: a
+/// two-entry map `{ <tag>: <variant-key>, <content>: <variant-body> }`, where the variant-body is
+/// decoded independently rather than merged into the outer map (contrast with the single-entry
+/// `tag`-less representation and the field-merging `tag`-only representation above).
+fn derive_enum_adjacently_tagged(dec: &Codable, variants: &[&Variant]) -> TokenStream {
+    let tag_key = dec.tag.as_ref().unwrap().to_cbor_key_expr();
+    let content_key = dec.content.as_ref().unwrap().to_cbor_key_expr();
+
+    let decoders: Vec<_> = variants
+        .iter()
+        .filter_map(|variant| {
+            if variant.skip.is_some() {
+                return None;
+            }
+
+            let variant_ident = &variant.ident;
+            let key = variant.to_cbor_key_expr(dec.rename_all.as_ref());
+
+            let decoder = if variant.fields.is_unit() && !variant.as_struct.is_some() {
+                quote!(Self::#variant_ident)
+            } else if variant.fields.is_newtype() {
+                let decode_fn =
+                    quote_spanned!(variant.ident.span()=> __cbor::Decode::try_from_cbor_value);
+                quote!(Self::#variant_ident(#decode_fn(value)?))
+            } else {
+                let inner = derive_struct(
+                    &variant.ident,
+                    false,
+                    variant.as_array.is_some(),
+                    variant.fields.as_ref(),
+                    quote!(Self::#variant_ident),
+                    variant.rename_all.as_ref(),
+                );
+                quote!({ #inner })
+            };
+
+            Some(quote! {
+                if key == #key {
+                    return Ok(#decoder);
+                }
+            })
+        })
+        .collect();
+
+    quote! {
+        match value {
+            __cbor::Value::Map(map) => {
+                if map.len() != 2 {
+                    return Err(__cbor::DecodeError::UnknownField);
+                }
+
+                let mut tag = None;
+                let mut value = None;
+                for (k, v) in map {
+                    if k == #tag_key {
+                        tag = Some(v);
+                    } else if k == #content_key {
+                        value = Some(v);
+                    } else {
+                        return Err(__cbor::DecodeError::UnknownField);
+                    }
+                }
+                let key = tag.ok_or_else(|| __cbor::DecodeError::MissingField.context("tag"))?;
+                let value = value.ok_or_else(|| __cbor::DecodeError::MissingField.context("content"))?;
+                let _ = &value;
+
+                #(#decoders)*
+
+                Err(__cbor::DecodeError::UnknownField)
+            }
+            _ => Err(__cbor::DecodeError::UnexpectedType),
+        }
+    }
+}
@@ -4,7 +4,7 @@ use quote::{quote, quote_spanned};
 use syn::{spanned::Spanned, DeriveInput, Ident, Index, Member};
 
 use crate::{
-    common::{Codable, Field, Variant},
+    common::{Codable, Field, Key, RenameRule, Variant},
     util,
 };
 
@@ -34,14 +34,18 @@ pub fn derive(input: DeriveInput) -> TokenStream {
 
     let derived = match enc.data.as_ref() {
         darling::ast::Data::Enum(variants) => derive_enum(&enc, variants),
-        darling::ast::Data::Struct(fields) => derive_struct(
-            &enc.ident,
-            enc.transparent.is_present(),
-            enc.as_array.is_present(),
-            false,
-            fields,
-            None,
-        ),
+        darling::ast::Data::Struct(fields) => {
+            let derived = derive_struct(
+                &enc.ident,
+                enc.transparent.is_present(),
+                enc.as_array.is_present(),
+                false,
+                fields,
+                None,
+                enc.rename_all.as_ref(),
+            );
+            wrap_in_tag(enc.tag.as_ref(), derived)
+        }
     };
 
     let enc_ty_ident = &enc.ident;
@@ -49,6 +53,17 @@ pub fn derive(input: DeriveInput) -> TokenStream {
     let enc_impl = derived.enc_impl;
     let opt_enc_impl = derived.opt_enc_impl;
 
+    // Sort every map's entries by their encoded key bytes, so this type's own `to_vec`/`to_value`
+    // already produce deterministic output (see `Codable::deterministic`).
+    let (enc_impl, opt_enc_impl) = if enc.deterministic.is_present() {
+        (
+            quote!(__cbor::canonical::canonicalize(#enc_impl)),
+            quote!(#opt_enc_impl.map(__cbor::canonical::canonicalize)),
+        )
+    } else {
+        (enc_impl, opt_enc_impl)
+    };
+
     // Implement the EncodeAsMap marker trait in case the type is known to encode as a map. This
     // allows operations to only operate on such types.
     let encode_as_map = if derived.encode_as_map {
@@ -78,6 +93,23 @@ pub fn derive(input: DeriveInput) -> TokenStream {
     })
 }
 
+/// Wraps a struct's encoded `Value` in a CBOR semantic tag, per its container-level
+/// `#[cbor(tag = N)]` (see [`Codable::tag`]'s struct-side meaning). A no-op if `tag` is absent.
+fn wrap_in_tag(tag: Option<&Key>, derived: DeriveResult) -> DeriveResult {
+    let tag_number = match tag.map(Key::as_u64) {
+        None => return derived,
+        Some(n) => n.expect("validated to be an integer"),
+    };
+
+    let enc_impl = derived.enc_impl;
+
+    DeriveResult {
+        enc_impl: quote! { __cbor::Value::Tag(#tag_number, ::std::boxed::Box::new({ #enc_impl })) },
+        opt_enc_impl: quote! { Some(self.into_cbor_value()) },
+        encode_as_map: false,
+    }
+}
+
 fn derive_struct(
     ident: &Ident,
     transparent: bool,
@@ -85,6 +117,7 @@ fn derive_struct(
     unit_as_struct: bool,
     fields: darling::ast::Fields<&Field>,
     field_bindings: Option<Vec<Ident>>,
+    rename_all: Option<&RenameRule>,
 ) -> DeriveResult {
     if fields.is_unit() && !unit_as_struct {
         return DeriveResult {
@@ -117,6 +150,7 @@ fn derive_struct(
     } else {
         // Process all fields and encode the structure as a map or array.
         let as_array = fields.is_tuple() || fields.is_newtype() || as_array;
+        let has_flatten = fields.iter().any(|field| field.flatten.is_present());
 
         let field_map_items: Vec<_> = fields
             .iter()
@@ -144,12 +178,41 @@ fn derive_struct(
                         }),
                 };
 
-                let field_value = if let Some(custom_encode_fn) = &field.serialize_with {
+                if field.flatten.is_present() {
+                    // Merge the field's own map entries into the enclosing map instead of nesting
+                    // them under the field's own key.
+                    if as_array {
+                        field
+                            .ident
+                            .span()
+                            .unwrap()
+                            .error("cannot use flatten attribute in arrays".to_string())
+                            .emit();
+                        return quote!({});
+                    }
+
+                    let into_map_fn =
+                        quote_spanned!(field_ty.span()=> __cbor::EncodeAsMap::into_cbor_map);
+                    return quote! { fields.extend(#into_map_fn(#field_binding)); };
+                }
+
+                let field_value = if let Some(with_mod) = &field.with {
+                    quote_spanned!(field_ty.span()=> #with_mod::encode(&#field_binding))
+                } else if let Some(custom_encode_fn) = &field.serialize_with {
                     quote_spanned!(field_ty.span()=> __cbor::Encode::into_cbor_value(#custom_encode_fn(&#field_binding)))
                 } else {
                     quote_spanned!(field_ty.span()=> __cbor::Encode::into_cbor_value(#field_binding))
                 };
 
+                let field_value = match field.tag {
+                    // Wrap this field's value in a CBOR semantic tag, like the container-level
+                    // `#[cbor(tag = N)]` but scoped to just this field.
+                    Some(tag_number) => {
+                        quote!(__cbor::Value::Tag(#tag_number, ::std::boxed::Box::new(#field_value)))
+                    }
+                    None => field_value,
+                };
+
                 if as_array {
                     // Output the fields as a CBOR array.
                     if field.skip_serializing_if.is_some() {
@@ -165,7 +228,7 @@ fn derive_struct(
                     quote! { fields.push(#field_value); }
                 } else {
                     // Output the fields as a CBOR map.
-                    let key = field.to_cbor_key_expr();
+                    let key = field.to_cbor_key_expr(rename_all);
 
                     if field.optional.is_present() {
                         // If the field is optional then we can omit it when it is equal to the
@@ -196,6 +259,14 @@ fn derive_struct(
 
         let value_ty = if as_array {
             quote! { __cbor::Value::Array(fields) }
+        } else if has_flatten {
+            // A flattened field's own entries are merged in at whatever order that field's
+            // `Encode` impl happened to produce them, which generally isn't canonical order
+            // relative to this struct's own fields. Decode always expects map entries in
+            // canonical order (see `destructure_cbor_map_peek_value_strict`), so flatten can
+            // only round-trip if we sort the merged result ourselves here, regardless of
+            // whether this struct opted into `#[cbor(deterministic)]`.
+            quote! { __cbor::canonical::canonicalize(__cbor::Value::Map(fields)) }
         } else {
             quote! { __cbor::Value::Map(fields) }
         };
@@ -241,6 +312,13 @@ fn derive_enum(enc: &Codable, variants: Vec<&Variant>) -> DeriveResult {
         if enc.untagged.is_present() || variant.missing.is_present() {
             // Untagged enum with just the inner type.
             quote!( #inner )
+        } else if let Some(content) = &enc.content {
+            // Adjacently tagged enum: the tag and the variant's body are sibling map entries,
+            // rather than the body's own entries being merged with the tag (internally tagged).
+            let tag = enc.tag.as_ref().unwrap().to_cbor_key_expr();
+            let content = content.to_cbor_key_expr();
+
+            quote!(__cbor::Value::Map(vec![(#tag, #key), (#content, #inner)]))
         } else if let Some(tag) = &enc.tag {
             // Internally tagged enum.
             let tag = tag.to_cbor_key_expr();
@@ -263,9 +341,12 @@ fn derive_enum(enc: &Codable, variants: Vec<&Variant>) -> DeriveResult {
         .iter()
         .map(|variant| {
             let variant_ident = &variant.ident;
-            let key = variant.to_cbor_key_expr();
+            let key = variant.to_cbor_key_expr(enc.rename_all.as_ref());
 
-            let encode_fn = if enc.tag.is_some() {
+            let encode_fn = if enc.tag.is_some() && enc.content.is_none() {
+                // Internally tagged: the newtype's own encoding must already be a map, since the
+                // tag gets merged into it. Adjacently/externally tagged enums have no such
+                // requirement, since the newtype's value becomes its own map entry.
                 quote_spanned!(variant.ident.span()=> __cbor::EncodeAsMap::into_cbor_value_map)
             } else {
                 quote_spanned!(variant.ident.span()=> __cbor::Encode::into_cbor_value)
@@ -283,7 +364,7 @@ fn derive_enum(enc: &Codable, variants: Vec<&Variant>) -> DeriveResult {
                     return (quote!(), false);
                 }
                 if enc.tag.is_some() {
-                    variant.ident.span().unwrap().error("cannot use embed attribute on internally tagged enum".to_string()).emit();
+                    variant.ident.span().unwrap().error("cannot use embed attribute on internally or adjacently tagged enum".to_string()).emit();
                     return (quote!(), false);
                 }
                 // TODO: It would be great if this somehow ensured that there was no overlap etc.
@@ -329,7 +410,7 @@ fn derive_enum(enc: &Codable, variants: Vec<&Variant>) -> DeriveResult {
                         })
                         .unzip();
 
-                    if enc.tag.is_some() && (variant.as_array.is_present() || variant.fields.is_tuple()) {
+                    if enc.tag.is_some() && enc.content.is_none() && (variant.as_array.is_present() || variant.fields.is_tuple()) {
                         variant.ident.span().unwrap().error("cannot encode variant as array in internally tagged enums".to_string()).emit();
                         return (quote!(), false);
                     }
@@ -342,6 +423,7 @@ fn derive_enum(enc: &Codable, variants: Vec<&Variant>) -> DeriveResult {
                         variant.as_struct.is_present(),
                         variant.fields.as_ref(),
                         Some(idents),
+                        variant.rename_all.as_ref(),
                     );
                     let inner = derived.enc_impl;
                     let wrapper = maybe_wrap_map(variant, key, quote!( {#inner} ));
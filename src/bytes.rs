@@ -0,0 +1,85 @@
+//! Byte-string encoding that doesn't rely on `min_specialization`.
+//!
+//! The crate's blanket `Vec<u8>`/`[u8; N]` impls (in [`crate::decode`]/[`crate::encode`]) need
+//! `#![feature(min_specialization)]` to override the generic `Vec<T>`/`[T; N]` array behavior,
+//! which pins users to nightly and doesn't reach a `Vec<u8>` sitting behind a generic type
+//! parameter (specialization only kicks in for the concrete type, not for some `T = Vec<u8>`).
+//! [`ByteVec`] and [`ByteArray`] sidestep that entirely by being their own, non-generic types
+//! with a direct `Value::ByteString` encoding -- no specialization needed. Use them directly, or
+//! apply `#[cbor(with = "oasis_cbor::bytes")]` to a plain `Vec<u8>` field to get the same
+//! encoding without changing the field's type.
+//!
+//! There is deliberately no borrowed `ByteSlice<'a>` counterpart: decoding into a borrow would
+//! need [`Value`] to carry the input's lifetime, which this crate doesn't control (see the
+//! module-level note on [`crate::decode`]).
+use crate::{Decode, DecodeError, Encode, Value};
+
+/// Encode `value` as a CBOR byte string. Used as the `encode` half of
+/// `#[cbor(with = "oasis_cbor::bytes")]` on a `Vec<u8>` field.
+pub fn encode(value: &Vec<u8>) -> Value {
+    Value::ByteString(value.clone())
+}
+
+/// Decode a CBOR byte string into a `Vec<u8>`. Used as the `decode` half of
+/// `#[cbor(with = "oasis_cbor::bytes")]` on a `Vec<u8>` field.
+pub fn decode(value: Value) -> Result<Vec<u8>, DecodeError> {
+    match value {
+        Value::ByteString(v) => Ok(v),
+        _ => Err(DecodeError::UnexpectedType),
+    }
+}
+
+/// A `Vec<u8>` that always encodes as a CBOR byte string, without needing
+/// `#![feature(min_specialization)]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ByteVec(pub Vec<u8>);
+
+impl Encode for ByteVec {
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn into_cbor_value(self) -> Value {
+        Value::ByteString(self.0)
+    }
+}
+
+impl Decode for ByteVec {
+    fn try_default() -> Result<Self, DecodeError> {
+        Ok(Default::default())
+    }
+
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::ByteString(v) => Ok(ByteVec(v)),
+            _ => Err(DecodeError::UnexpectedType),
+        }
+    }
+}
+
+/// A `[u8; N]` that always encodes as a CBOR byte string, without needing
+/// `#![feature(min_specialization)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteArray<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Encode for ByteArray<N> {
+    fn into_cbor_value(self) -> Value {
+        Value::ByteString(self.0.into())
+    }
+}
+
+impl<const N: usize> Decode for ByteArray<N> {
+    fn try_default() -> Result<Self, DecodeError> {
+        Ok(ByteArray([0u8; N]))
+    }
+
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::ByteString(v) => v
+                .try_into()
+                .map(ByteArray)
+                .map_err(|_| DecodeError::UnexpectedType),
+            _ => Err(DecodeError::UnexpectedType),
+        }
+    }
+}
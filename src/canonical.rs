@@ -0,0 +1,69 @@
+//! Deterministic (canonical) CBOR map-key ordering, per RFC 8949 §4.2.1.
+//!
+//! This module covers the map-key-ordering half of RFC 8949 §4.2's deterministic-encoding rules
+//! on both directions: [`canonicalize`] enforces it while encoding, [`check_canonical`] while
+//! decoding. The other half of §4.2 -- rejecting integer/length heads that aren't in their
+//! shortest possible form -- can only be checked against the raw wire bytes, which this crate
+//! never sees itself (see [`crate::from_slice`]'s doc); enforcing that rule would need support
+//! added to the underlying `sk_cbor` reader, so it's out of scope here.
+use crate::{writer, DecodeError, Value};
+
+/// Recursively sorts every `Value::Map`'s entries by the bytewise lexicographic order of their
+/// CBOR-encoded keys.
+///
+/// This is the core-deterministic-encoding ordering rule from RFC 8949 §4.2.1: two encoders
+/// that disagree on map/struct field order still produce byte-identical output once their
+/// `Value` trees are passed through here before writing.
+pub fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.into_iter().map(canonicalize).collect()),
+        Value::Map(items) => {
+            let mut items: Vec<_> = items
+                .into_iter()
+                .map(|(k, v)| (encoded_key_bytes(&k), canonicalize(k), canonicalize(v)))
+                .collect();
+            items.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Map(items.into_iter().map(|(_, k, v)| (k, v)).collect())
+        }
+        Value::Tag(tag, inner) => Value::Tag(tag, Box::new(canonicalize(*inner))),
+        other => other,
+    }
+}
+
+fn encoded_key_bytes(key: &Value) -> Vec<u8> {
+    let mut buf = vec![];
+    writer::write(key.clone(), &mut buf).expect("encoding a map key should not fail");
+    buf
+}
+
+/// Recursively checks that every `Value::Map` in `value` has its entries in strictly ascending
+/// canonical key order (the same order [`canonicalize`] produces), rejecting both out-of-order
+/// and duplicate keys.
+///
+/// Used by [`crate::from_slice_canonical`]/[`crate::from_value_canonical`] to reject input that
+/// isn't already in deterministic form, rather than silently accepting it the way plain
+/// [`crate::from_slice`]/[`crate::from_value`] do.
+pub fn check_canonical(value: &Value) -> Result<(), DecodeError> {
+    match value {
+        Value::Array(items) => items.iter().try_for_each(check_canonical),
+        Value::Map(items) => {
+            let mut prev: Option<Vec<u8>> = None;
+            for (k, v) in items {
+                let key_bytes = encoded_key_bytes(k);
+                if let Some(prev) = &prev {
+                    match key_bytes.cmp(prev) {
+                        std::cmp::Ordering::Equal => return Err(DecodeError::DuplicateMapKey),
+                        std::cmp::Ordering::Less => return Err(DecodeError::MapKeyOrdering),
+                        std::cmp::Ordering::Greater => {}
+                    }
+                }
+                check_canonical(k)?;
+                check_canonical(v)?;
+                prev = Some(key_bytes);
+            }
+            Ok(())
+        }
+        Value::Tag(_, inner) => check_canonical(inner),
+        _ => Ok(()),
+    }
+}
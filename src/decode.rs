@@ -1,8 +1,23 @@
 //! CBOR decoding.
+//!
+//! All decoding goes through the intermediate [`Value`] representation provided by the
+//! underlying `sk_cbor` reader, which always materializes byte and text strings as owned
+//! `Vec<u8>`/`String` buffers. A zero-copy decode path (returning `&'de [u8]`/`&'de str` that
+//! borrow directly from the input buffer) would require `Value` itself to carry an input
+//! lifetime, which is not something this crate controls -- that would have to happen in
+//! `sk_cbor` first. Until then, [`Decode`] stays unparameterized and every decoded string or
+//! byte string is an owned allocation.
 use std::{
+    borrow::Cow,
     cmp::Ordering,
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
     convert::TryInto,
+    num::{
+        NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU64,
+        NonZeroU8,
+    },
+    rc::Rc,
+    sync::Arc,
 };
 
 use impl_trait_for_tuples::impl_for_tuples;
@@ -47,8 +62,18 @@ impl Decode for Tuple {
 
     fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
         match value {
-            Value::Array(mut values) => {
-                Ok((for_tuples!( #( Tuple::try_from_cbor_value(values.remove(0))? ),* )))
+            Value::Array(values) => {
+                // `values.remove(0)` would panic if the array is shorter than the tuple, so
+                // check the length up front and report it as a regular decode error instead.
+                let expected = for_tuples!( #( { let _ = Tuple::try_default; 1usize } )+* );
+                if values.len() != expected {
+                    return Err(DecodeError::TupleSize {
+                        expected,
+                        found: values.len(),
+                    });
+                }
+                let mut it = values.into_iter();
+                Ok((for_tuples!( #( Tuple::try_from_cbor_value(it.next().unwrap())? ),* )))
             }
             _ => Err(DecodeError::UnexpectedType),
         }
@@ -157,6 +182,10 @@ impl Decode for String {
         Ok(Default::default())
     }
 
+    // `value` already arrives as an owned `String` (`Value::TextString` holds one), copied out
+    // of the input buffer by the reader before we ever see it -- there's no borrow left to hand
+    // back here. A `&str`/`&[u8]` impl would need `Value` to carry the input lifetime instead,
+    // see the module-level note above.
     fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
         match value {
             Value::TextString(v) => Ok(v),
@@ -165,6 +194,12 @@ impl Decode for String {
     }
 }
 
+// Whether the `Value::Array`/`Value::Map` a `Vec`/`BTreeMap`/etc. decodes from was itself
+// assembled from a definite-length or an indefinite-length (chunked, `0xFF`-terminated) wire
+// encoding is already resolved by the time it reaches here: the reader hands us a flat,
+// already-collected `Vec<Value>`/`Vec<(Value, Value)>` either way. So nothing in this file needs
+// to change to support indefinite-length containers -- that support has to be added to the
+// reader itself, which lives outside this crate.
 impl<T: Decode> Decode for Vec<T> {
     default fn try_default() -> Result<Self, DecodeError> {
         Ok(Default::default())
@@ -172,7 +207,13 @@ impl<T: Decode> Decode for Vec<T> {
 
     default fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
         match value {
-            Value::Array(v) => v.into_iter().map(T::try_from_cbor_value).collect(),
+            Value::Array(v) => v
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    T::try_from_cbor_value(item).map_err(|e| e.context(format!("[{i}]")))
+                })
+                .collect(),
             _ => Err(DecodeError::UnexpectedType),
         }
     }
@@ -241,6 +282,123 @@ impl Decode for Value {
     }
 }
 
+impl<T: Decode> Decode for Box<T> {
+    fn try_default() -> Result<Self, DecodeError> {
+        Ok(Box::new(T::try_default()?))
+    }
+
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        Ok(Box::new(T::try_from_cbor_value(value)?))
+    }
+}
+
+impl<T: Decode> Decode for Rc<T> {
+    fn try_default() -> Result<Self, DecodeError> {
+        Ok(Rc::new(T::try_default()?))
+    }
+
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        Ok(Rc::new(T::try_from_cbor_value(value)?))
+    }
+}
+
+impl<T: Decode> Decode for Arc<T> {
+    fn try_default() -> Result<Self, DecodeError> {
+        Ok(Arc::new(T::try_default()?))
+    }
+
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        Ok(Arc::new(T::try_from_cbor_value(value)?))
+    }
+}
+
+impl<T: Decode + Clone> Decode for Cow<'_, T> {
+    fn try_default() -> Result<Self, DecodeError> {
+        Ok(Cow::Owned(T::try_default()?))
+    }
+
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        Ok(Cow::Owned(T::try_from_cbor_value(value)?))
+    }
+}
+
+impl<T: Decode> Decode for VecDeque<T> {
+    fn try_default() -> Result<Self, DecodeError> {
+        Ok(Default::default())
+    }
+
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::Array(v) => v
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    T::try_from_cbor_value(item).map_err(|e| e.context(format!("[{i}]")))
+                })
+                .collect(),
+            _ => Err(DecodeError::UnexpectedType),
+        }
+    }
+}
+
+impl<T: Decode> Decode for LinkedList<T> {
+    fn try_default() -> Result<Self, DecodeError> {
+        Ok(Default::default())
+    }
+
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::Array(v) => v
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    T::try_from_cbor_value(item).map_err(|e| e.context(format!("[{i}]")))
+                })
+                .collect(),
+            _ => Err(DecodeError::UnexpectedType),
+        }
+    }
+}
+
+impl<T: Decode + Ord> Decode for BinaryHeap<T> {
+    fn try_default() -> Result<Self, DecodeError> {
+        Ok(Default::default())
+    }
+
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::Array(v) => v
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    T::try_from_cbor_value(item).map_err(|e| e.context(format!("[{i}]")))
+                })
+                .collect(),
+            _ => Err(DecodeError::UnexpectedType),
+        }
+    }
+}
+
+macro_rules! impl_nonzero {
+    ($nonzero:ty, $prim:ty) => {
+        impl Decode for $nonzero {
+            fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+                let v = <$prim>::try_from_cbor_value(value)?;
+                <$nonzero>::new(v).ok_or(DecodeError::UnexpectedZero)
+            }
+        }
+    };
+}
+
+impl_nonzero!(NonZeroU8, u8);
+impl_nonzero!(NonZeroU16, u16);
+impl_nonzero!(NonZeroU32, u32);
+impl_nonzero!(NonZeroU64, u64);
+impl_nonzero!(NonZeroI8, i8);
+impl_nonzero!(NonZeroI16, i16);
+impl_nonzero!(NonZeroI32, i32);
+impl_nonzero!(NonZeroI64, i64);
+
 impl<K: Decode + Ord, V: Decode> Decode for BTreeMap<K, V> {
     fn try_default() -> Result<Self, DecodeError> {
         Ok(Default::default())
@@ -249,11 +407,15 @@ impl<K: Decode + Ord, V: Decode> Decode for BTreeMap<K, V> {
     fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
         match value {
             Value::Map(v) => {
-                let result: Result<Vec<_>, DecodeError> = v
-                    .into_iter()
-                    .map(|(k, v)| Ok((K::try_from_cbor_value(k)?, V::try_from_cbor_value(v)?)))
-                    .collect();
-                Ok(result?.into_iter().collect())
+                let mut map = Self::new();
+                for (k, v) in v {
+                    let k = K::try_from_cbor_value(k)?;
+                    let v = V::try_from_cbor_value(v)?;
+                    if map.insert(k, v).is_some() {
+                        return Err(DecodeError::DuplicateMapKey);
+                    }
+                }
+                Ok(map)
             }
             _ => Err(DecodeError::UnexpectedType),
         }
@@ -281,11 +443,15 @@ impl<K: Decode + Eq + std::hash::Hash, V: Decode> Decode for HashMap<K, V> {
     fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
         match value {
             Value::Map(v) => {
-                let result: Result<Vec<_>, DecodeError> = v
-                    .into_iter()
-                    .map(|(k, v)| Ok((K::try_from_cbor_value(k)?, V::try_from_cbor_value(v)?)))
-                    .collect();
-                Ok(result?.into_iter().collect())
+                let mut map = Self::new();
+                for (k, v) in v {
+                    let k = K::try_from_cbor_value(k)?;
+                    let v = V::try_from_cbor_value(v)?;
+                    if map.insert(k, v).is_some() {
+                        return Err(DecodeError::DuplicateMapKey);
+                    }
+                }
+                Ok(map)
             }
             _ => Err(DecodeError::UnexpectedType),
         }
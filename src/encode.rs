@@ -1,5 +1,27 @@
 //! CBOR encoding.
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+//!
+//! There are deliberately no `Encode`/`Decode` impls for `f32`/`f64` here: major type 7
+//! (floating-point/simple) values aren't supported by the underlying `sk_cbor` reader/writer at
+//! all (see `serde::test::test_float`, which locks in that `sk_cbor` rejects float-bearing
+//! input with `DecoderError::UnsupportedFloatingPointValue`), and [`Value`] itself has no variant
+//! to hold one. So there is no native CBOR float wire representation this crate could target,
+//! and no `is_empty`-treats-`0.0`-as-empty or decode-any-width-into-the-requested-type behavior
+//! (the kind `Decode`/`Encode` give integers) to add, until that dependency gains float support.
+//!
+//! The practical workaround, if lossless float transport is needed today, lives one layer up: a
+//! non-native encoding (e.g. a tagged byte string carrying the bits) can still round-trip through
+//! `Value` just fine, since it never needs major type 7 at all. That's the shape the `serde`
+//! opt-in float fallback takes, rather than anything here.
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
+    num::{
+        NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU64,
+        NonZeroU8,
+    },
+    rc::Rc,
+    sync::Arc,
+};
 
 use impl_trait_for_tuples::impl_for_tuples;
 
@@ -241,6 +263,101 @@ impl Encode for Value {
     }
 }
 
+impl<T: Encode> Encode for Box<T> {
+    fn is_empty(&self) -> bool {
+        self.as_ref().is_empty()
+    }
+
+    fn into_cbor_value(self) -> Value {
+        (*self).into_cbor_value()
+    }
+}
+
+impl<T: Encode + Clone> Encode for Rc<T> {
+    fn is_empty(&self) -> bool {
+        self.as_ref().is_empty()
+    }
+
+    fn into_cbor_value(self) -> Value {
+        match Rc::try_unwrap(self) {
+            Ok(v) => v.into_cbor_value(),
+            Err(rc) => (*rc).clone().into_cbor_value(),
+        }
+    }
+}
+
+impl<T: Encode + Clone> Encode for Arc<T> {
+    fn is_empty(&self) -> bool {
+        self.as_ref().is_empty()
+    }
+
+    fn into_cbor_value(self) -> Value {
+        match Arc::try_unwrap(self) {
+            Ok(v) => v.into_cbor_value(),
+            Err(arc) => (*arc).clone().into_cbor_value(),
+        }
+    }
+}
+
+impl<T: Encode + Clone> Encode for Cow<'_, T> {
+    fn is_empty(&self) -> bool {
+        self.as_ref().is_empty()
+    }
+
+    fn into_cbor_value(self) -> Value {
+        self.into_owned().into_cbor_value()
+    }
+}
+
+impl<T: Encode> Encode for VecDeque<T> {
+    fn is_empty(&self) -> bool {
+        VecDeque::is_empty(self)
+    }
+
+    fn into_cbor_value(self) -> Value {
+        Value::Array(self.into_iter().map(Encode::into_cbor_value).collect())
+    }
+}
+
+impl<T: Encode> Encode for LinkedList<T> {
+    fn is_empty(&self) -> bool {
+        LinkedList::is_empty(self)
+    }
+
+    fn into_cbor_value(self) -> Value {
+        Value::Array(self.into_iter().map(Encode::into_cbor_value).collect())
+    }
+}
+
+impl<T: Encode + Ord> Encode for BinaryHeap<T> {
+    fn is_empty(&self) -> bool {
+        BinaryHeap::is_empty(self)
+    }
+
+    fn into_cbor_value(self) -> Value {
+        Value::Array(self.into_iter().map(Encode::into_cbor_value).collect())
+    }
+}
+
+macro_rules! impl_nonzero {
+    ($nonzero:ty) => {
+        impl Encode for $nonzero {
+            fn into_cbor_value(self) -> Value {
+                self.get().into_cbor_value()
+            }
+        }
+    };
+}
+
+impl_nonzero!(NonZeroU8);
+impl_nonzero!(NonZeroU16);
+impl_nonzero!(NonZeroU32);
+impl_nonzero!(NonZeroU64);
+impl_nonzero!(NonZeroI8);
+impl_nonzero!(NonZeroI16);
+impl_nonzero!(NonZeroI32);
+impl_nonzero!(NonZeroI64);
+
 impl<K: Encode, V: Encode> Encode for BTreeMap<K, V> {
     fn is_empty(&self) -> bool {
         BTreeMap::is_empty(self)
@@ -2,12 +2,16 @@
 #![feature(min_specialization)]
 #![feature(trait_alias)]
 
+pub mod bytes;
+pub mod canonical;
+pub mod context;
 pub mod decode;
 pub mod encode;
 #[doc(hidden)]
 pub mod macros;
 #[cfg(feature = "serde")]
 pub mod serde;
+pub mod tag;
 
 pub use oasis_cbor_derive::*; // Re-export the support proc-macros.
 pub use oasis_cbor_value::*;
@@ -15,14 +19,23 @@ use thiserror::Error;
 
 // Re-export traits.
 pub use crate::{
+    context::{DecodeWithContext, EncodeWithContext},
     decode::Decode,
     encode::{Encode, EncodeAsMap},
+    tag::{Tag, Tagged},
 };
 
 /// Maximum nesting level allowed when decoding from CBOR.
 const MAX_NESTING_LEVEL: i8 = 64;
 
 /// Error encountered during decoding.
+///
+/// The existing variants stay unit-like (no payload) rather than growing a mandatory field name
+/// or similar: several of them are returned from fully generic code (e.g. `Option<T>::try_default`,
+/// the blanket container impls) that has no field name to attach in the first place. Instead,
+/// [`DecodeError::context`] lets any caller that *does* know where it is -- a derived struct
+/// decoding a field, a `Vec` decoding an element -- wrap the error with that location after the
+/// fact via [`DecodeError::WithContext`].
 #[derive(Debug, Error)]
 pub enum DecodeError {
     #[error("parsing failed")]
@@ -35,6 +48,58 @@ pub enum DecodeError {
     UnknownField,
     #[error("unexpected integer size")]
     UnexpectedIntegerSize,
+    #[error("unexpected tag")]
+    UnexpectedTag,
+    #[error("duplicate map key")]
+    DuplicateMapKey,
+    /// A map's entries were not sorted by the bytewise lexicographic order of their CBOR-encoded
+    /// keys. Only ever returned by [`from_slice_canonical`]/[`from_value_canonical`]; plain
+    /// [`from_slice`] rejects out-of-order keys as [`DecodeError::ParsingFailed`], while
+    /// [`from_slice_non_strict`] and [`from_value`] accept maps in any order.
+    #[error("map keys are not in canonical order")]
+    MapKeyOrdering,
+    #[error("depth limit exceeded")]
+    DepthLimitExceeded,
+    #[error("expected a non-zero value")]
+    UnexpectedZero,
+    /// A tuple did not have exactly the expected number of elements. Derived array-represented
+    /// structs and variants report too few elements as [`DecodeError::MissingField`] and too many
+    /// as [`DecodeError::UnknownField`] instead.
+    #[error("expected tuple of size {expected}, got {found}")]
+    TupleSize { expected: usize, found: usize },
+    /// Wraps an error with the map key, array index, or field name that was being decoded when
+    /// it occurred, so nested structures produce paths like `foo.bar[3]: unexpected type`
+    /// instead of just `unexpected type`.
+    #[error("{path}{}", context_tail(.source))]
+    WithContext {
+        path: String,
+        #[source]
+        source: Box<DecodeError>,
+    },
+}
+
+impl DecodeError {
+    /// Wraps `self` in a [`DecodeError::WithContext`], prepending `path` to any context already
+    /// attached, so errors from nested containers read outside-in (`foo.bar[3]: ...`).
+    pub fn context(self, path: impl Into<String>) -> Self {
+        DecodeError::WithContext {
+            path: path.into(),
+            source: Box::new(self),
+        }
+    }
+}
+
+/// Renders what follows the outermost path segment of a [`DecodeError::WithContext`] chain:
+/// nested segments joined with `.` (index segments like `[3]` appended directly), then `: ` and
+/// the innermost error.
+fn context_tail(source: &DecodeError) -> String {
+    match source {
+        DecodeError::WithContext { path, source } if path.starts_with('[') => {
+            format!("{path}{}", context_tail(source))
+        }
+        DecodeError::WithContext { path, source } => format!(".{path}{}", context_tail(source)),
+        other => format!(": {other}"),
+    }
 }
 
 impl From<reader::DecoderError> for DecodeError {
@@ -44,6 +109,17 @@ impl From<reader::DecoderError> for DecodeError {
 }
 
 /// Convert CBOR-encoded data into the given type.
+///
+/// `Map`-backed types (`BTreeMap`, `HashMap`, and anything a derived struct/enum decodes maps
+/// into) reject duplicate keys with [`DecodeError::DuplicateMapKey`] when they decode a map
+/// (e.g. through [`from_value`] or [`from_slice_non_strict`]), since a decoder that silently
+/// kept the last occurrence would let two different byte strings decode to the same logical
+/// value. This function reads with the strict reader, which already rejects a map whose keys
+/// are out of order or repeated as [`DecodeError::ParsingFailed`] before any type's decode
+/// runs. See [`from_slice_canonical`] for the RFC 8949 §4.2.1 canonical-order check.
+/// Rejecting non-shortest-form integer/length heads, the remaining deterministic-encoding rule
+/// from that section, can only be checked while looking at the raw bytes, which happens in the
+/// underlying reader rather than here, so this crate cannot enforce it on its own while decoding.
 pub fn from_slice<T>(data: &[u8]) -> Result<T, DecodeError>
 where
     T: Decode,
@@ -53,6 +129,12 @@ where
 }
 
 /// Convert CBOR-encoded data into the given type using non-strict decoding.
+///
+/// Note that whether indefinite-length arrays, maps and strings (`0x9F`/`0xBF`/`0x5F`/`0x7F`
+/// headers terminated by a standalone `0xFF` break) can be ingested here depends entirely on
+/// the underlying `reader::read_nested_non_strict` implementation: this crate only ever sees
+/// the fully-materialized [`Value`] tree it produces, so indefinite-length support would need
+/// to be added to that reader rather than here.
 pub fn from_slice_non_strict<T>(data: &[u8]) -> Result<T, DecodeError>
 where
     T: Decode,
@@ -61,9 +143,52 @@ where
     T::try_from_cbor_value_default(value)
 }
 
+/// Convert CBOR-encoded data into the given type, threading a caller-supplied context `C`
+/// through the decode. See [`DecodeWithContext`].
+pub fn from_slice_with<T, C>(data: &[u8], ctx: &mut C) -> Result<T, DecodeError>
+where
+    T: DecodeWithContext<C>,
+{
+    let value = reader::read_nested(data, Some(MAX_NESTING_LEVEL))?;
+    T::try_from_cbor_value_with(value, ctx)
+}
+
+/// Configurable limits enforced while decoding untrusted CBOR input.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Maximum nesting depth (of arrays, maps and tags) allowed while decoding.
+    pub max_depth: i8,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_depth: MAX_NESTING_LEVEL,
+        }
+    }
+}
+
+/// Convert CBOR-encoded data into the given type, enforcing the given decoding `Limits`.
+///
+/// This only lets callers tighten (or loosen) the nesting depth, since that is the only guard
+/// the underlying reader exposes a hook for. Capping a container's up-front allocation at its
+/// declared length (so a few truncated bytes can't claim a multi-gigabyte array) would need a
+/// matching hook added to that reader, which this crate does not own.
+pub fn from_slice_with_limits<T>(data: &[u8], limits: Limits) -> Result<T, DecodeError>
+where
+    T: Decode,
+{
+    let value = reader::read_nested(data, Some(limits.max_depth))?;
+    T::try_from_cbor_value_default(value)
+}
+
 /// Convert high-level CBOR representation into the given type.
 ///
-/// This is the same as calling `T::try_from_cbor_value(value)`.
+/// This is the same as calling `T::try_from_cbor_value(value)`. [`Value`] is already the
+/// schema-less, decode-to-inspect-then-convert tree this crate uses throughout (see
+/// `to_value`/`from_value` for the two directions); we can't additionally provide a blanket
+/// `impl<T: Decode> TryFrom<Value> for T` ourselves, since neither `T` nor `Value` is a type
+/// local to this crate, which the orphan rule requires.
 pub fn from_value<T>(value: Value) -> Result<T, DecodeError>
 where
     T: Decode,
@@ -71,6 +196,43 @@ where
     T::try_from_cbor_value_default(value)
 }
 
+/// Convert a high-level CBOR representation into the given type, enforcing the given decoding
+/// `Limits`.
+///
+/// Unlike [`from_slice_with_limits`], this operates on an already-materialized [`Value`] tree,
+/// so the nesting-depth limit is enforced here directly rather than relying on a reader hook.
+pub fn from_value_with_limits<T>(value: Value, limits: Limits) -> Result<T, DecodeError>
+where
+    T: Decode,
+{
+    check_value_depth(&value, 0, limits.max_depth)?;
+    T::try_from_cbor_value_default(value)
+}
+
+/// Recursively checks that `value` does not nest deeper than `max_depth` (arrays, maps and tags
+/// each count as one level).
+fn check_value_depth(value: &Value, depth: i8, max_depth: i8) -> Result<(), DecodeError> {
+    if depth > max_depth {
+        return Err(DecodeError::DepthLimitExceeded);
+    }
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                check_value_depth(item, depth + 1, max_depth)?;
+            }
+        }
+        Value::Map(items) => {
+            for (k, v) in items {
+                check_value_depth(k, depth + 1, max_depth)?;
+                check_value_depth(v, depth + 1, max_depth)?;
+            }
+        }
+        Value::Tag(_, inner) => check_value_depth(inner, depth + 1, max_depth)?,
+        _ => {}
+    }
+    Ok(())
+}
+
 /// Convert the given type into its CBOR-encoded representation.
 pub fn to_vec<T>(value: T) -> Vec<u8>
 where
@@ -90,3 +252,66 @@ where
 {
     value.into_cbor_value()
 }
+
+/// Convert the given type into its CBOR-encoded representation, with every map's entries sorted
+/// by their encoded key bytes (the map-key-ordering rule of RFC 8949 §4.2.1 deterministic
+/// encoding).
+///
+/// Two values that only differ in field/map-entry order produce identical output through this
+/// function, which plain [`to_vec`] does not guarantee.
+pub fn to_vec_canonical<T>(value: T) -> Vec<u8>
+where
+    T: Encode,
+{
+    let mut data = vec![];
+    writer::write(canonical::canonicalize(value.into_cbor_value()), &mut data).unwrap();
+    data
+}
+
+/// Convert the given type into its high-level CBOR representation, with every map's entries
+/// sorted by their encoded key bytes. See [`to_vec_canonical`].
+pub fn to_value_canonical<T>(value: T) -> Value
+where
+    T: Encode,
+{
+    canonical::canonicalize(value.into_cbor_value())
+}
+
+/// Convert CBOR-encoded data into the given type, rejecting input whose maps are not already in
+/// RFC 8949 §4.2.1 canonical (sorted, duplicate-free) key order.
+///
+/// Unlike plain [`from_slice`], this additionally walks the fully-parsed [`Value`] tree up front
+/// and returns [`DecodeError::MapKeyOrdering`] or [`DecodeError::DuplicateMapKey`] the first time
+/// it finds a map that isn't. Pair with
+/// [`to_vec_canonical`] on the encoding side for consensus-critical code that needs a single
+/// canonical byte representation per logical value.
+pub fn from_slice_canonical<T>(data: &[u8]) -> Result<T, DecodeError>
+where
+    T: Decode,
+{
+    let value = reader::read_nested(data, Some(MAX_NESTING_LEVEL))?;
+    canonical::check_canonical(&value)?;
+    T::try_from_cbor_value_default(value)
+}
+
+/// Convert a high-level CBOR representation into the given type, rejecting input whose maps are
+/// not already in RFC 8949 §4.2.1 canonical (sorted, duplicate-free) key order. See
+/// [`from_slice_canonical`].
+pub fn from_value_canonical<T>(value: Value) -> Result<T, DecodeError>
+where
+    T: Decode,
+{
+    canonical::check_canonical(&value)?;
+    T::try_from_cbor_value_default(value)
+}
+
+/// Convert the given type into its CBOR-encoded representation, threading a caller-supplied
+/// context `C` through the encode. See [`EncodeWithContext`].
+pub fn to_vec_with<T, C>(value: T, ctx: &mut C) -> Vec<u8>
+where
+    T: EncodeWithContext<C>,
+{
+    let mut data = vec![];
+    writer::write(value.into_cbor_value_with(ctx), &mut data).unwrap();
+    data
+}
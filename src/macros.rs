@@ -1,28 +1,62 @@
-use std::{cmp::Ordering, iter::Peekable};
+use std::cmp::Ordering;
 
 use crate::{values::Value, DecodeError};
 
 /// This function is an internal detail of the Decode derive macro, but has public visibility so
 /// that users of the macro can use it.
+///
+/// `map` must be sorted by key, and is consumed front-to-back as fields are matched in the same
+/// order. An entry sorting before `needle` means some key was left behind by an earlier field, so
+/// it is rejected as unknown rather than skipped.
 pub fn destructure_cbor_map_peek_value_strict(
-    it: &mut Peekable<std::vec::IntoIter<(Value, Value)>>,
+    map: &mut Vec<(Value, Value)>,
     needle: Value,
 ) -> Result<Option<Value>, DecodeError> {
-    match it.peek() {
+    match map.first() {
         None => Ok(None),
-        Some(item) => {
-            let key: &Value = &item.0;
-            match key.cmp(&needle) {
-                Ordering::Less => {
-                    // Reject unexpected fields.
-                    Err(DecodeError::UnknownField)
-                }
-                Ordering::Equal => {
-                    let value: Value = it.next().unwrap().1;
-                    Ok(Some(value))
-                }
-                Ordering::Greater => Ok(None),
+        Some((key, _)) => match key.cmp(&needle) {
+            Ordering::Less => {
+                // Reject unexpected fields.
+                Err(DecodeError::UnknownField)
             }
-        }
+            Ordering::Equal => Ok(Some(map.remove(0).1)),
+            Ordering::Greater => Ok(None),
+        },
     }
 }
+
+/// This function is an internal detail of the Decode derive macro, but has public visibility so
+/// that users of the macro can use it.
+///
+/// Like [`destructure_cbor_map_peek_value_strict`], but treats a miss -- whether `needle` sorts
+/// before or after the front entry -- as "not found" (`None`) instead of erroring on the
+/// before case. Used for fields that have `#[cbor(alias)]` keys: an alias need not sort adjacent
+/// to the field's primary key, so a front entry that sorts before `needle` isn't necessarily an
+/// unknown field, just one [`destructure_cbor_map_alias_value`] still needs a chance to match
+/// against an alias. Any entry that's still unmatched once every field (and alias) has been
+/// probed is caught by the caller's final unknown-field check.
+pub fn destructure_cbor_map_peek_value(
+    map: &mut Vec<(Value, Value)>,
+    needle: Value,
+) -> Option<Value> {
+    match map.first() {
+        Some((key, _)) if *key == needle => Some(map.remove(0).1),
+        _ => None,
+    }
+}
+
+/// This function is an internal detail of the Decode derive macro, but has public visibility so
+/// that users of the macro can use it.
+///
+/// Scans the remainder of `map` for `needle`, removing and returning its value if present. Unlike
+/// [`destructure_cbor_map_peek_value_strict`], `needle` need not be the next entry in sorted
+/// order, and entries skipped over are left in place rather than rejected. This backs
+/// `#[cbor(alias)]`: an alias key's encoded position need not fall where the field's primary key
+/// would have sorted, so it has to be probed for out of order once the primary lookup misses.
+pub fn destructure_cbor_map_alias_value(
+    map: &mut Vec<(Value, Value)>,
+    needle: Value,
+) -> Option<Value> {
+    let pos = map.iter().position(|(key, _)| *key == needle)?;
+    Some(map.remove(pos).1)
+}
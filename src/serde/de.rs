@@ -45,12 +45,53 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         bool i8 i16 i32 i64 i128 u8 u16 u32 u64
         str string identifier
         bytes byte_buf
-        newtype_struct tuple_struct
-        tuple
         unit unit_struct
         ignored_any
     }
 
+    // `tuple`/`tuple_struct` can't go through `forward_to_deserialize_any!` like the rest: a
+    // `Value::ByteString` standing in for an all-`u8` tuple (see the encode-side `OnlyU8Probe`
+    // that produces it) needs `deserialize_seq`'s `ByteString -> Unsigned` coercion, but
+    // `deserialize_any`'s `Value::ByteString` arm calls `visit_byte_buf`, which serde's generated
+    // tuple `Visitor`s don't implement -- only `visit_seq`. Route both through `deserialize_seq`
+    // directly instead.
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if name == crate::serde::TAGGED_NAME {
+            // Symmetric counterpart of the `Serializer`'s `@@TAG@@` handling: a `Value::Tag` is
+            // fed back to `Tagged::deserialize`'s visitor as a `(tag, inner)` pair.
+            return match self.0 {
+                Value::Tag(tag, inner) => visitor.visit_seq(TaggedSeqAccess {
+                    tag: Some(tag),
+                    inner: Some(*inner),
+                }),
+                _ => Err(Error::invalid_type(unexpected(&self.0), &"cbor tag")),
+            };
+        }
+        self.deserialize_any(visitor)
+    }
+
     fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
@@ -114,6 +155,13 @@ impl<'de> de::Deserializer<'de> for Deserializer {
 
         let items = match self.0 {
             Value::Array(a) => a,
+            // serde's blanket `Vec<u8>: Deserialize` impl always goes through `deserialize_seq`
+            // (see the encode-side doc on `Vec<u8>`/`[u8]` for the same serde-309 limitation), so
+            // a byte string must be visitable as a seq of `u8` too, not just an array.
+            Value::ByteString(b) => b
+                .into_iter()
+                .map(|byte| Value::Unsigned(byte as u64))
+                .collect(),
             _ => return Err(Error::invalid_type(unexpected(&self.0), &"array")),
         };
         visitor.visit_seq(SeqAccessImpl(items.into_iter()))
@@ -241,4 +289,33 @@ impl<'de> de::Deserializer<'de> for Deserializer {
             )),
         }
     }
+
+    /// See the matching override on the `Serializer` in the adjacent module.
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Feeds a `Value::Tag`'s `(tag, inner)` pair to a two-element `visit_seq`, for
+/// `deserialize_newtype_struct`'s `@@TAG@@` handling.
+struct TaggedSeqAccess {
+    tag: Option<u64>,
+    inner: Option<Value>,
+}
+
+impl<'de> de::SeqAccess<'de> for TaggedSeqAccess {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if let Some(tag) = self.tag.take() {
+            return seed.deserialize(Deserializer(Value::Unsigned(tag))).map(Some);
+        }
+        if let Some(inner) = self.inner.take() {
+            return seed.deserialize(Deserializer(inner)).map(Some);
+        }
+        Ok(None)
+    }
 }
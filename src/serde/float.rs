@@ -0,0 +1,87 @@
+//! Opt-in, lossless `f32`/`f64` support for the serde layer.
+//!
+//! `sk_cbor` has no CBOR float major type, so the default `Serializer`/`Deserializer` hard-reject
+//! `f32`/`f64` (see `test_float` in [`super::test`]) rather than silently losing precision or
+//! picking an arbitrary lossy encoding. [`LosslessF32`]/[`LosslessF64`] are an opt-in escape
+//! hatch for structs that do need a float field: they encode the value's IEEE-754 bit pattern as
+//! a big-endian byte string wrapped in a private-use [`crate::Tagged`], which round-trips
+//! NaN/Inf bit-exactly without needing float support anywhere in the underlying reader/writer.
+//! Use them directly, or with `#[serde(with = "...")]`-style field wrappers, wherever the
+//! default strict rejection isn't wanted.
+use serde::{de, Deserialize, Serialize};
+
+use crate::Tagged;
+
+/// Private-use CBOR tag marking a losslessly-encoded `f32` (its 4-byte big-endian bit pattern).
+/// Not assigned by the IANA CBOR tag registry; pick different numbers if they ever collide with
+/// tags actually used elsewhere in a given application.
+const F32_TAG: u64 = 30000;
+
+/// Private-use CBOR tag marking a losslessly-encoded `f64` (its 8-byte big-endian bit pattern).
+const F64_TAG: u64 = 30001;
+
+/// An `f32` that (de)serializes losslessly through the serde layer, bypassing the default
+/// `f32`/`f64` rejection. See the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LosslessF32(pub f32);
+
+impl Serialize for LosslessF32 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Tagged {
+            tag: F32_TAG,
+            value: serde_bytes::Bytes::new(&self.0.to_be_bytes()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LosslessF32 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tagged = Tagged::<serde_bytes::ByteBuf>::deserialize(deserializer)?;
+        if tagged.tag != F32_TAG {
+            return Err(de::Error::custom(format!(
+                "expected CBOR tag {}, found {}",
+                F32_TAG, tagged.tag
+            )));
+        }
+        let bytes: [u8; 4] = tagged
+            .value
+            .into_vec()
+            .try_into()
+            .map_err(|_| de::Error::custom("expected 4 bytes for a lossless f32"))?;
+        Ok(LosslessF32(f32::from_be_bytes(bytes)))
+    }
+}
+
+/// An `f64` that (de)serializes losslessly through the serde layer, bypassing the default
+/// `f32`/`f64` rejection. See the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LosslessF64(pub f64);
+
+impl Serialize for LosslessF64 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Tagged {
+            tag: F64_TAG,
+            value: serde_bytes::Bytes::new(&self.0.to_be_bytes()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LosslessF64 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tagged = Tagged::<serde_bytes::ByteBuf>::deserialize(deserializer)?;
+        if tagged.tag != F64_TAG {
+            return Err(de::Error::custom(format!(
+                "expected CBOR tag {}, found {}",
+                F64_TAG, tagged.tag
+            )));
+        }
+        let bytes: [u8; 8] = tagged
+            .value
+            .into_vec()
+            .try_into()
+            .map_err(|_| de::Error::custom("expected 8 bytes for a lossless f64"))?;
+        Ok(LosslessF64(f64::from_be_bytes(bytes)))
+    }
+}
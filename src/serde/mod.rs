@@ -10,13 +10,24 @@
 
 mod de;
 mod error;
+mod float;
 mod ser;
 #[cfg(test)]
 mod test;
 
-pub use self::error::Error;
+pub use self::{
+    error::Error,
+    float::{LosslessF32, LosslessF64},
+};
 use crate::Value;
 
+/// Magic newtype-struct name that [`crate::Tagged`] serializes itself under, so the
+/// [`ser::Serializer`]/[`de::Deserializer`] in this module can recognize it and convert to/from
+/// `Value::Tag` instead of the newtype's usual encoding. Serde's data model has no native concept
+/// of a CBOR tag, so this name is the only channel available to smuggle one through it; picking
+/// an `@@...@@`-shaped name follows the same trick `serde_bytes` uses for byte strings.
+pub(crate) const TAGGED_NAME: &str = "@@TAG@@";
+
 /// Deserialize CBOR-encoded bytes into `T`.
 pub fn from_slice<T>(data: &[u8]) -> Result<T, Error>
 where
@@ -54,3 +65,28 @@ where
     let mut ser = ser::Serializer;
     value.serialize(&mut ser)
 }
+
+/// Serialize `value` into CBOR bytes, with every map's entries sorted by their encoded key bytes
+/// (the map-key-ordering rule of RFC 8949 §4.2.1 deterministic encoding). See
+/// [`crate::to_vec_canonical`] for the equivalent over `#[derive(Encode)]` types.
+///
+/// Two values that only differ in field/map-entry order -- including `HashMap`s that iterate in
+/// different orders -- produce identical output through this function, which plain [`to_vec`]
+/// does not guarantee.
+pub fn to_vec_canonical<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: ?Sized + serde::Serialize,
+{
+    let mut data = vec![];
+    sk_cbor::writer::write(to_value_canonical(value)?, &mut data).map_err(Error::from)?;
+    Ok(data)
+}
+
+/// Serialize `value` into intermediate-representation `sk_cbor::Value`, with every map's entries
+/// sorted by their encoded key bytes. See [`to_vec_canonical`].
+pub fn to_value_canonical<T>(value: &T) -> Result<Value, Error>
+where
+    T: ?Sized + serde::Serialize,
+{
+    Ok(crate::canonical::canonicalize(to_value(value)?))
+}
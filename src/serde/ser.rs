@@ -85,9 +85,30 @@ impl<'a> ser::Serializer for &'a mut Serializer {
 
     fn serialize_newtype_struct<T: ?Sized + Serialize>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
+        if name == crate::serde::TAGGED_NAME {
+            // `value` is the `(tag, inner)` pair that `Tagged::serialize` wraps itself in; unwrap
+            // it into a `Value::Tag` instead of encoding it as a regular two-element array.
+            return match value.serialize(&mut *self)? {
+                Value::Array(mut items) if items.len() == 2 => {
+                    let inner = items.pop().unwrap();
+                    let tag = match items.pop().unwrap() {
+                        Value::Unsigned(tag) => tag,
+                        _ => {
+                            return Err(Self::Error::Other(
+                                "CBOR tag number must be an unsigned integer".to_owned(),
+                            ))
+                        }
+                    };
+                    Ok(Value::Tag(tag, Box::new(inner)))
+                }
+                _ => Err(Self::Error::Other(
+                    "malformed @@TAG@@ newtype wrapper".to_owned(),
+                )),
+            };
+        }
         (&[value]).serialize(self)
     }
 
@@ -105,11 +126,15 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        Ok(SeqSerializer { items: vec![] })
+        Ok(SeqSerializer {
+            state: SeqState::Undecided,
+        })
     }
 
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        Ok(SeqSerializer { items: vec![] })
+        Ok(SeqSerializer {
+            state: SeqState::Undecided,
+        })
     }
 
     fn serialize_tuple_struct(
@@ -160,10 +185,27 @@ impl<'a> ser::Serializer for &'a mut Serializer {
             fields: vec![],
         })
     }
+
+    /// CBOR is a binary format, so ecosystem types that branch on this flag (e.g. `uuid`,
+    /// `std::net::IpAddr` wrappers) should pick their compact binary representation rather than a
+    /// verbose textual one.
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Accumulation state for [`SeqSerializer`]. Serde cannot special-case `[u8]`/`Vec<u8>`
+/// (https://github.com/serde-rs/serde/issues/309), so every sequence starts out `Undecided` and
+/// only commits to the compact `Value::ByteString` encoding once its first element actually
+/// turns out to be a plain `u8` -- the "OnlyBytes" probe technique `rmp-serde` uses.
+enum SeqState {
+    Undecided,
+    Bytes(Vec<u8>),
+    Values(Vec<Value>),
 }
 
 pub(crate) struct SeqSerializer {
-    items: Vec<Value>,
+    state: SeqState,
 }
 
 impl<'a> ser::SerializeTuple for SeqSerializer {
@@ -171,12 +213,182 @@ impl<'a> ser::SerializeTuple for SeqSerializer {
     type Error = Error;
 
     fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-        self.items.push(value.serialize(&mut Serializer)?);
+        match &mut self.state {
+            SeqState::Undecided => {
+                self.state = match value.serialize(OnlyU8Probe) {
+                    Ok(byte) => SeqState::Bytes(vec![byte]),
+                    Err(_) => SeqState::Values(vec![value.serialize(&mut Serializer)?]),
+                };
+            }
+            SeqState::Bytes(bytes) => match value.serialize(OnlyU8Probe) {
+                Ok(byte) => bytes.push(byte),
+                Err(_) => {
+                    // The sequence is heterogeneous after all: replay the bytes collected so far
+                    // as the `Value::Unsigned`s they would have become without the probe.
+                    let mut values: Vec<Value> =
+                        bytes.iter().map(|&b| Value::Unsigned(b as u64)).collect();
+                    values.push(value.serialize(&mut Serializer)?);
+                    self.state = SeqState::Values(values);
+                }
+            },
+            SeqState::Values(values) => values.push(value.serialize(&mut Serializer)?),
+        }
         Ok(())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Array(self.items))
+        Ok(match self.state {
+            SeqState::Undecided => Value::Array(vec![]),
+            SeqState::Bytes(bytes) => Value::ByteString(bytes),
+            SeqState::Values(values) => Value::Array(values),
+        })
+    }
+}
+
+/// Serializer used by the "OnlyBytes" probe: if serializing a sequence element through this
+/// succeeds, the element is a plain `u8` and [`SeqSerializer`] can keep accumulating it as a
+/// `Value::ByteString`; any other value fails every method here, and the sequence falls back to
+/// accumulating a `Value::Array` instead.
+struct OnlyU8Probe;
+
+impl OnlyU8Probe {
+    fn not_a_byte() -> Error {
+        Error::Other("not a byte".to_owned())
+    }
+}
+
+/// Derives probe impls for serialize_X() methods that always report "not a byte".
+macro_rules! reject_scalar_fns {
+    ($(fn $name:ident($ty:ty);)*) => {
+        $(
+        fn $name(self, _v: $ty) -> Result<Self::Ok, Self::Error> {
+            Err(Self::not_a_byte())
+        }
+        )*
+    };
+}
+
+impl ser::Serializer for OnlyU8Probe {
+    type Ok = u8;
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<u8, Error>;
+    type SerializeTuple = ser::Impossible<u8, Error>;
+    type SerializeTupleStruct = ser::Impossible<u8, Error>;
+    type SerializeTupleVariant = ser::Impossible<u8, Error>;
+    type SerializeMap = ser::Impossible<u8, Error>;
+    type SerializeStruct = ser::Impossible<u8, Error>;
+    type SerializeStructVariant = ser::Impossible<u8, Error>;
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        Ok(v)
+    }
+
+    reject_scalar_fns! {
+        fn serialize_bool(bool);
+        fn serialize_i8(i8);
+        fn serialize_i16(i16);
+        fn serialize_i32(i32);
+        fn serialize_i64(i64);
+        fn serialize_u16(u16);
+        fn serialize_u32(u32);
+        fn serialize_u64(u64);
+        fn serialize_f32(f32);
+        fn serialize_f64(f64);
+        fn serialize_char(char);
+        fn serialize_str(&str);
+        fn serialize_bytes(&[u8]);
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(Self::not_a_byte())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(Self::not_a_byte())
     }
 }
 
@@ -1,7 +1,7 @@
 use oasis_cbor_derive::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 
-use crate::{SimpleValue, Value};
+use crate::{SimpleValue, Tagged, Value};
 
 macro_rules! str {
     ($s:expr) => {
@@ -191,20 +191,28 @@ fn test_map() {
 
 #[test]
 fn test_bytes() {
-    // NOTE: oasis_cbor encodes [u8] as `Value::ByteString`. By contrast, this implementation
-    // encodes it as `Value::Array` because the `serde` framework cannot special-case
-    // the serialization of [u8] compared to [T]: https://github.com/serde-rs/serde/issues/309
-    // So we cannot assert_compat_roundtrip() here.
+    // Unlike serde in general -- which cannot special-case the serialization of [u8] compared
+    // to [T] (https://github.com/serde-rs/serde/issues/309) -- the `Serializer` here probes a
+    // sequence's first element, so a `Vec<u8>` still ends up as the same compact
+    // `Value::ByteString` that bare oasis_cbor produces.
+    assert_compat_roundtrip(vec![31u8, 11], Value::ByteString(vec![31, 11]));
+
+    // A heterogeneous-looking sequence that merely starts with a byte still falls back to
+    // `Value::Array` once a later element turns out not to be a `u8`.
     assert_serde_roundtrip(
-        vec![31u8, 11],
-        Value::Array(vec![Value::Unsigned(31), Value::Unsigned(11)]),
+        (1u8, "two".to_string()),
+        Value::Array(vec![Value::Unsigned(1), str!("two")]),
     );
 
-    // To efficiently encode bytes slices with serde, use the serde_bytes wrapper type.
-    // This encodes as a `Value::BytesString` (and has minimal overhead).
+    // The serde_bytes wrapper type still works and encodes the same way.
     let hello_bytes = vec![104, 101, 108, 108, 111];
     let wrapped_bytes = serde_bytes::ByteBuf::from(&*hello_bytes);
     assert_serde_roundtrip(wrapped_bytes, Value::ByteString(hello_bytes));
+
+    // An all-`u8` tuple goes through the same `Value::ByteString` compaction as a `Vec<u8>`, but
+    // needs to come back out through `deserialize_tuple`/`deserialize_seq` rather than
+    // `deserialize_any`, since serde's generated tuple `Visitor`s only implement `visit_seq`.
+    assert_compat_roundtrip((31u8, 11u8), Value::ByteString(vec![31, 11]));
 }
 
 #[test]
@@ -355,3 +363,140 @@ mod structs {
         assert_compat_roundtrip(v, Value::Simple(SimpleValue::NullValue));
     }
 }
+
+#[test]
+fn test_canonical_map_key_ordering() {
+    // HashMap iteration order is unspecified, so insert these in an order that's unlikely to
+    // already be sorted by encoded key bytes, and check that to_vec_canonical() sorts them.
+    let mut m = std::collections::HashMap::new();
+    m.insert("zzz".to_string(), 1u8);
+    m.insert("a".to_string(), 2u8);
+    m.insert("mm".to_string(), 3u8);
+
+    let canonical = crate::serde::to_value_canonical(&m).unwrap();
+    assert_eq!(
+        canonical,
+        Value::Map(vec![
+            (str!("a"), Value::Unsigned(2)),
+            (str!("mm"), Value::Unsigned(3)),
+            (str!("zzz"), Value::Unsigned(1)),
+        ]),
+        "map entries should be sorted by encoded key bytes"
+    );
+
+    let bytes = crate::serde::to_vec_canonical(&m).unwrap();
+    let reconstructed: std::collections::HashMap<String, u8> =
+        crate::serde::from_slice(&bytes).unwrap();
+    assert_eq!(m, reconstructed);
+}
+
+mod lossless_float {
+    use super::*;
+    use crate::serde::{LosslessF32, LosslessF64};
+
+    #[test]
+    fn test_lossless_f32() {
+        for v in [0.0f32, -0.0, 1.5, f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let bytes = crate::serde::to_vec(&LosslessF32(v)).unwrap();
+            let LosslessF32(reconstructed) = crate::serde::from_slice(&bytes).unwrap();
+            assert_eq!(
+                v.to_bits(),
+                reconstructed.to_bits(),
+                "f32 {:?} did not round-trip bit-exactly",
+                v
+            );
+        }
+    }
+
+    #[test]
+    fn test_lossless_f64() {
+        for v in [0.0f64, -0.0, 1.5, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let bytes = crate::serde::to_vec(&LosslessF64(v)).unwrap();
+            let LosslessF64(reconstructed) = crate::serde::from_slice(&bytes).unwrap();
+            assert_eq!(
+                v.to_bits(),
+                reconstructed.to_bits(),
+                "f64 {:?} did not round-trip bit-exactly",
+                v
+            );
+        }
+    }
+
+    #[test]
+    fn test_lossless_float_is_tagged() {
+        // The representation is a private-use CBOR tag wrapping an 8-byte string, not a bare
+        // float major type (which sk_cbor doesn't have).
+        let value = crate::serde::to_value(&LosslessF64(1.5)).unwrap();
+        assert!(
+            matches!(value, Value::Tag(_, _)),
+            "expected a CBOR tag, got {:?}",
+            value
+        );
+    }
+}
+
+#[test]
+fn test_not_human_readable() {
+    // A stand-in for ecosystem types (`uuid::Uuid`, `std::net::IpAddr` wrappers, ...) whose
+    // (de)serialization branches on `is_human_readable()`, picking a compact binary
+    // representation when it is false and a verbose textual one otherwise.
+    struct HumanReadableProbe(bool);
+
+    impl Serialize for HumanReadableProbe {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bool(serializer.is_human_readable())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for HumanReadableProbe {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Ok(HumanReadableProbe(deserializer.is_human_readable()))
+        }
+    }
+
+    let bytes = crate::serde::to_vec(&HumanReadableProbe(true)).unwrap();
+    let HumanReadableProbe(human_readable) = crate::serde::from_slice(&bytes).unwrap();
+    assert!(
+        !human_readable,
+        "oasis_cbor's serde Serializer/Deserializer should report is_human_readable() == false"
+    );
+}
+
+mod tags {
+    use super::*;
+
+    #[test]
+    fn test_tagged() {
+        // Tag 32: URI, per the CBOR tag registry.
+        assert_compat_roundtrip(
+            Tagged {
+                tag: 32,
+                value: "https://example.com".to_string(),
+            },
+            Value::Tag(32, Box::new(str!("https://example.com"))),
+        );
+    }
+
+    #[test]
+    fn test_tagged_nested() {
+        #[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Encode, Decode, Clone)]
+        struct Timestamped {
+            // Tag 1: epoch-based date/time.
+            when: Tagged<u64>,
+            label: String,
+        }
+        assert_compat_roundtrip(
+            Timestamped {
+                when: Tagged { tag: 1, value: 1_700_000_000 },
+                label: "launch".to_string(),
+            },
+            Value::Map(vec![
+                (str!("label"), str!("launch")),
+                (
+                    str!("when"),
+                    Value::Tag(1, Box::new(Value::Unsigned(1_700_000_000))),
+                ),
+            ]),
+        );
+    }
+}
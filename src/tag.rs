@@ -0,0 +1,103 @@
+//! Support for CBOR semantic tags (major type 6).
+use crate::{Decode, DecodeError, Encode, Value};
+
+/// Wraps a value with a CBOR semantic tag (major type 6) whose tag number is fixed at compile
+/// time.
+///
+/// Encoding emits `Value::Tag(N, Box::new(inner))`. Decoding checks that the tag number found
+/// on the wire is `N`, returning [`DecodeError::UnexpectedTag`] if it is any other tag number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tag<const N: u64, T>(pub T);
+
+impl<const N: u64, T: Encode> Encode for Tag<N, T> {
+    fn into_cbor_value(self) -> Value {
+        Value::Tag(N, Box::new(self.0.into_cbor_value()))
+    }
+}
+
+impl<const N: u64, T: Decode> Decode for Tag<N, T> {
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::Tag(tag, inner) if tag == N => Ok(Tag(T::try_from_cbor_value(*inner)?)),
+            Value::Tag(_, _) => Err(DecodeError::UnexpectedTag),
+            _ => Err(DecodeError::UnexpectedType),
+        }
+    }
+}
+
+/// Wraps a value together with a CBOR semantic tag number that is only known at runtime.
+///
+/// Unlike [`Tag`], which checks the tag number on decode against a compile-time constant,
+/// `Tagged` accepts any tag number and exposes it via the `tag` field. Use this when the tag
+/// number is data-dependent (e.g. selecting between several well-known tags) rather than fixed
+/// per field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tagged<T> {
+    pub tag: u64,
+    pub value: T,
+}
+
+impl<T: Encode> Encode for Tagged<T> {
+    fn into_cbor_value(self) -> Value {
+        Value::Tag(self.tag, Box::new(self.value.into_cbor_value()))
+    }
+}
+
+impl<T: Decode> Decode for Tagged<T> {
+    fn try_from_cbor_value(value: Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::Tag(tag, inner) => Ok(Tagged {
+                tag,
+                value: T::try_from_cbor_value(*inner)?,
+            }),
+            _ => Err(DecodeError::UnexpectedType),
+        }
+    }
+}
+
+/// Lets `Tagged<T>` carry a CBOR semantic tag through `#[derive(Serialize, Deserialize)]` code,
+/// even though serde's data model has no native notion of a tag. `Tagged` serializes itself as a
+/// newtype struct under the reserved name [`crate::serde::TAGGED_NAME`]; the `Serializer`/
+/// `Deserializer` in [`crate::serde`] recognize that name and convert to/from `Value::Tag`
+/// instead of the newtype's usual encoding. See that module for the other half.
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{de, ser, Deserialize, Serialize};
+
+    use super::Tagged;
+
+    impl<T: Serialize> Serialize for Tagged<T> {
+        fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_newtype_struct(crate::serde::TAGGED_NAME, &(self.tag, &self.value))
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for Tagged<T> {
+        fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct TaggedVisitor<T>(std::marker::PhantomData<T>);
+
+            impl<'de, T: Deserialize<'de>> de::Visitor<'de> for TaggedVisitor<T> {
+                type Value = Tagged<T>;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str("a CBOR-tagged value")
+                }
+
+                fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                    let tag = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                    let value = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                    Ok(Tagged { tag, value })
+                }
+            }
+
+            deserializer.deserialize_newtype_struct(
+                crate::serde::TAGGED_NAME,
+                TaggedVisitor(std::marker::PhantomData),
+            )
+        }
+    }
+}
@@ -1,6 +1,12 @@
 extern crate alloc;
 
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
+    num::NonZeroU32,
+    rc::Rc,
+    sync::Arc,
+};
 
 use oasis_cbor as cbor;
 
@@ -77,6 +83,88 @@ struct WithOptionalDefault {
     bar: String,
 }
 
+fn default_count() -> NonZeroU32 {
+    NonZeroU32::new(1).unwrap()
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+struct WithCustomDefault {
+    // `NonZeroU32` cannot decode a null value, so the null fallback that plain `optional` uses
+    // would not work here; `default` supplies a path to call instead.
+    #[cbor(optional, default = "default_count")]
+    count: NonZeroU32,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+struct WithBareDefault {
+    #[cbor(optional, default)]
+    bar: String,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+struct WithAlias {
+    #[cbor(rename = "new_name", alias = "old_name", alias = "older_name")]
+    bar: String,
+    foo: u64,
+}
+
+// `WithAlias`'s own aliases ("old_name", "older_name") both sort after its renamed primary key
+// ("new_name"), so a map entry using them never precedes the primary key in the wire format.
+// This fixture's alias ("aaa") sorts before its primary key ("zzz"), to exercise the case where
+// the strict front-peek for the primary key would see the alias entry and need to defer to the
+// alias scan instead of rejecting it outright.
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+struct WithAliasSortingBeforePrimary {
+    #[cbor(rename = "zzz", alias = "aaa")]
+    bar: String,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+struct Header {
+    version: u64,
+    name: String,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+struct WithFlatten {
+    id: u64,
+    #[cbor(flatten)]
+    header: Header,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+#[cbor(tag = 1000, deterministic)]
+struct WithContainerTag {
+    foo: u64,
+    bar: String,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+#[cbor(deterministic)]
+struct WithFieldTag {
+    foo: u64,
+    #[cbor(tag = 32)]
+    bar: String,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+#[cbor(deterministic)]
+struct DeterministicOrder {
+    second: u64,
+    first: u64,
+    thirdd: u64,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+enum MessageWithFlattenedHeader {
+    #[cbor(rename = "ping")]
+    Ping {
+        #[cbor(flatten)]
+        header: Header,
+        nonce: u64,
+    },
+}
+
 #[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
 struct WithOptional {
     #[cbor(optional)]
@@ -124,10 +212,12 @@ struct WithNonOptionalUnit {
 #[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
 struct Unit;
 
-#[derive(Debug, Clone, Eq, PartialEq, cbor::Encode)]
+#[derive(Debug, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
 #[cbor(untagged)]
 enum Untagged {
     First { a: u64, b: u64 },
+    Second(u64),
+    Third,
 }
 
 #[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
@@ -137,6 +227,13 @@ struct AsArray {
     bytes: Vec<u8>,
 }
 
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+struct WithBytesAttribute {
+    foo: u64,
+    #[cbor(with = "cbor::bytes")]
+    raw: Vec<u8>,
+}
+
 #[derive(Debug, Default, Clone, Eq, PartialEq)] // No cbor::{Encode, Decode}!
 struct CustomType(String);
 
@@ -271,6 +368,43 @@ enum InternallyTaggedNoMissing {
     V2(Order),
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, cbor::Decode, cbor::Encode)]
+#[cbor(tag = "t", content = "c")]
+enum AdjacentlyTagged {
+    #[cbor(rename = 0)]
+    V0,
+
+    #[cbor(rename = 1)]
+    V1 { bar: u64 },
+
+    #[cbor(rename = 2)]
+    V2(Order),
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+#[cbor(rename_all = "camelCase")]
+struct RenameAllFields {
+    foo_bar: u64,
+    #[cbor(rename = "explicit")]
+    baz_qux: bool,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+#[cbor(rename_all = "camelCase")]
+struct RenameAllWithIntegerKey {
+    #[cbor(rename = 7)]
+    foo_bar: u64,
+    baz_qux: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, cbor::Encode, cbor::Decode)]
+#[cbor(rename_all = "SCREAMING_SNAKE_CASE")]
+enum RenameAllVariants {
+    FirstVariant,
+    #[cbor(rename_all = "camelCase")]
+    SecondVariant { inner_value: u64 },
+}
+
 #[test]
 fn test_round_trip_complex() {
     let a = A {
@@ -447,6 +581,95 @@ fn test_tuple_struct() {
     assert_eq!(dec, e, "serialization should round-trip");
 }
 
+#[test]
+fn test_tuple_size_mismatch() {
+    let short = vec![
+        // [500, "string"] -- missing the trailing bool.
+        0x82, // array(2)
+        0x19, 0x01, 0xF4, // unsigned(500)
+        0x66, // text(6)
+        0x73, 0x74, 0x72, 0x69, 0x6E, 0x67, // "string"
+    ];
+    let err = cbor::from_slice::<(u64, String, bool)>(&short).expect_err("array too short");
+    assert!(matches!(
+        err,
+        cbor::DecodeError::TupleSize {
+            expected: 3,
+            found: 2
+        }
+    ));
+}
+
+#[test]
+fn test_decode_error_context() {
+    // Build an `A` by hand with `nested.foo` holding a text string instead of an unsigned int,
+    // and check the resulting error names both the outer and inner field it occurred under.
+    use cbor::{SimpleValue, Value};
+
+    let value = Value::Map(vec![
+        (Value::TextString("always".to_owned()), Value::Simple(SimpleValue::NullValue)),
+        (Value::TextString("bar".to_owned()), Value::TextString("bar".to_owned())),
+        (Value::TextString("different".to_owned()), Value::Simple(SimpleValue::FalseValue)),
+        (Value::TextString("foo".to_owned()), Value::Unsigned(10)),
+        (
+            Value::TextString("nested".to_owned()),
+            Value::Map(vec![
+                (Value::TextString("bytes".to_owned()), Value::ByteString(b"here".to_vec())),
+                (Value::TextString("foo".to_owned()), Value::TextString("not a number".to_owned())),
+            ]),
+        ),
+    ]);
+
+    let err = cbor::from_value::<A>(value).expect_err("field type mismatch");
+    match err {
+        cbor::DecodeError::WithContext { path, source } => {
+            assert_eq!(path, "nested");
+            match *source {
+                cbor::DecodeError::WithContext { path, source } => {
+                    assert_eq!(path, "foo");
+                    assert!(matches!(*source, cbor::DecodeError::UnexpectedType));
+                }
+                other => panic!("expected nested WithContext, got {other:?}"),
+            }
+        }
+        other => panic!("expected WithContext, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_decode_error_context_missing_field() {
+    // Same as `test_decode_error_context`, but `nested.bytes` is missing entirely instead of
+    // holding the wrong type -- the missing-field case must carry the same path context as every
+    // other field-level failure, not a bare `MissingField` with nothing pointing at `nested`.
+    use cbor::{SimpleValue, Value};
+
+    let value = Value::Map(vec![
+        (Value::TextString("always".to_owned()), Value::Simple(SimpleValue::NullValue)),
+        (Value::TextString("bar".to_owned()), Value::TextString("bar".to_owned())),
+        (Value::TextString("different".to_owned()), Value::Simple(SimpleValue::FalseValue)),
+        (Value::TextString("foo".to_owned()), Value::Unsigned(10)),
+        (
+            Value::TextString("nested".to_owned()),
+            Value::Map(vec![(Value::TextString("foo".to_owned()), Value::Unsigned(1))]),
+        ),
+    ]);
+
+    let err = cbor::from_value::<A>(value).expect_err("missing required field");
+    match err {
+        cbor::DecodeError::WithContext { path, source } => {
+            assert_eq!(path, "nested");
+            match *source {
+                cbor::DecodeError::WithContext { path, source } => {
+                    assert_eq!(path, "bytes");
+                    assert!(matches!(*source, cbor::DecodeError::MissingField));
+                }
+                other => panic!("expected nested WithContext, got {other:?}"),
+            }
+        }
+        other => panic!("expected WithContext, got {other:?}"),
+    }
+}
+
 #[test]
 fn test_transparent() {
     let transparent = Transparent(42);
@@ -634,6 +857,47 @@ fn test_bigint() {
     }
 }
 
+#[test]
+fn test_tag() {
+    let t = cbor::Tag::<32, _>("https://oasisprotocol.org/".to_string());
+    let enc = cbor::to_vec(t.clone());
+    assert_eq!(
+        enc,
+        vec![
+            0xd8, 0x20, // tag(32)
+            0x78, 0x1a, // text(26)
+            0x68, 0x74, 0x74, 0x70, 0x73, 0x3a, 0x2f, 0x2f, 0x6f, 0x61, 0x73, 0x69, 0x73, 0x70,
+            0x72, 0x6f, 0x74, 0x6f, 0x63, 0x6f, 0x6c, 0x2e, 0x6f, 0x72, 0x67, 0x2f,
+        ]
+    );
+
+    let dec: cbor::Tag<32, String> = cbor::from_slice(&enc).expect("decoding should succeed");
+    assert_eq!(dec, t, "serialization should round-trip");
+
+    // A mismatched tag number should be rejected.
+    let err = cbor::from_slice::<cbor::Tag<0, String>>(&enc).expect_err("tag should mismatch");
+    assert!(matches!(err, cbor::DecodeError::UnexpectedTag));
+}
+
+#[test]
+fn test_tagged() {
+    let t = cbor::Tagged {
+        tag: 1,
+        value: 1_000_000u64,
+    };
+    let enc = cbor::to_vec(t.clone());
+    assert_eq!(
+        enc,
+        vec![
+            0xc1, // tag(1)
+            0x1a, 0x00, 0x0f, 0x42, 0x40, // unsigned(1_000_000)
+        ]
+    );
+
+    let dec: cbor::Tagged<u64> = cbor::from_slice(&enc).expect("decoding should succeed");
+    assert_eq!(dec, t, "serialization should round-trip");
+}
+
 #[test]
 fn test_uint64() {
     let tcs = vec![
@@ -678,6 +942,258 @@ fn test_with_default() {
     assert_eq!(enc, vec![0xA0]);
 }
 
+#[test]
+fn test_custom_default() {
+    // Missing key invokes the configured path instead of decoding a null value.
+    let dec: WithCustomDefault = cbor::from_slice(&[0xA0]).expect("missing key should decode");
+    assert_eq!(dec, WithCustomDefault { count: default_count() });
+
+    // A present value is decoded normally.
+    let present = WithCustomDefault {
+        count: NonZeroU32::new(7).unwrap(),
+    };
+    let enc = cbor::to_vec(present.clone());
+    let dec: WithCustomDefault = cbor::from_slice(&enc).expect("serialization should round-trip");
+    assert_eq!(dec, present, "serialization should round-trip");
+
+    // A bare `default` flag uses `Default::default()`.
+    let dec: WithBareDefault = cbor::from_slice(&[0xA0]).expect("missing key should decode");
+    assert_eq!(dec, WithBareDefault::default());
+}
+
+#[test]
+fn test_with_alias() {
+    use cbor::Value;
+
+    // The primary (renamed) key decodes normally.
+    let value = Value::Map(vec![
+        (Value::TextString("foo".to_owned()), Value::Unsigned(1)),
+        (Value::TextString("new_name".to_owned()), Value::TextString("hi".to_owned())),
+    ]);
+    let dec: WithAlias = cbor::from_value(value).expect("primary key should decode");
+    assert_eq!(dec, WithAlias { bar: "hi".to_owned(), foo: 1 });
+
+    // An alias is probed once the primary key is missing.
+    let value = Value::Map(vec![
+        (Value::TextString("foo".to_owned()), Value::Unsigned(2)),
+        (Value::TextString("old_name".to_owned()), Value::TextString("legacy".to_owned())),
+    ]);
+    let dec: WithAlias = cbor::from_value(value).expect("first alias key should decode");
+    assert_eq!(dec, WithAlias { bar: "legacy".to_owned(), foo: 2 });
+
+    // A later alias is tried too.
+    let value = Value::Map(vec![
+        (Value::TextString("foo".to_owned()), Value::Unsigned(3)),
+        (Value::TextString("older_name".to_owned()), Value::TextString("ancient".to_owned())),
+    ]);
+    let dec: WithAlias = cbor::from_value(value).expect("second alias key should decode");
+    assert_eq!(dec, WithAlias { bar: "ancient".to_owned(), foo: 3 });
+
+    // Encoding always uses the primary (renamed) key, never an alias.
+    let original = WithAlias { bar: "hi".to_owned(), foo: 1 };
+    let enc = cbor::to_vec(original.clone());
+    let dec: WithAlias = cbor::from_slice(&enc).expect("round-trip");
+    assert_eq!(dec, original);
+}
+
+#[test]
+fn test_with_alias_sorting_before_primary() {
+    use cbor::Value;
+
+    // "aaa" sorts before the primary key "zzz", so the strict front-peek for "zzz" would see
+    // "aaa" and (absent the alias-aware peek) immediately reject it as an unknown field instead
+    // of letting the alias scan match it.
+    let value = Value::Map(vec![(
+        Value::TextString("aaa".to_owned()),
+        Value::TextString("legacy".to_owned()),
+    )]);
+    let dec: WithAliasSortingBeforePrimary =
+        cbor::from_value(value).expect("alias sorting before the primary key should still decode");
+    assert_eq!(
+        dec,
+        WithAliasSortingBeforePrimary {
+            bar: "legacy".to_owned()
+        }
+    );
+}
+
+#[test]
+fn test_with_flatten() {
+    use cbor::Value;
+
+    // The flattened struct's fields are merged into the enclosing map, not nested under "header".
+    let value = WithFlatten {
+        id: 1,
+        header: Header {
+            version: 2,
+            name: "test".to_owned(),
+        },
+    };
+    let enc = cbor::to_value(value.clone());
+    assert_eq!(
+        enc,
+        Value::Map(vec![
+            (Value::TextString("id".to_owned()), Value::Unsigned(1)),
+            (
+                Value::TextString("name".to_owned()),
+                Value::TextString("test".to_owned())
+            ),
+            (Value::TextString("version".to_owned()), Value::Unsigned(2)),
+        ]),
+        "flatten's merged entries should already be in canonical order"
+    );
+
+    // Flatten's own round trip must work directly on its own encode output, not just on a
+    // hand-sorted stand-in for it.
+    let dec: WithFlatten = cbor::from_value(enc).expect("flattened fields should decode");
+    assert_eq!(dec, value);
+}
+
+#[test]
+fn test_with_container_tag() {
+    use cbor::Value;
+
+    let value = WithContainerTag {
+        foo: 1,
+        bar: "hi".to_owned(),
+    };
+    let enc = cbor::to_value(value.clone());
+    match &enc {
+        Value::Tag(1000, inner) => assert!(matches!(**inner, Value::Map(_))),
+        _ => panic!("expected a tagged map"),
+    }
+
+    let dec: WithContainerTag = cbor::from_value(enc).expect("tagged value should decode");
+    assert_eq!(dec, value);
+
+    // A mismatched tag number is rejected.
+    let wrong_tag = Value::Tag(
+        1001,
+        Box::new(Value::Map(vec![
+            (Value::TextString("foo".to_owned()), Value::Unsigned(1)),
+            (
+                Value::TextString("bar".to_owned()),
+                Value::TextString("hi".to_owned()),
+            ),
+        ])),
+    );
+    let err = cbor::from_value::<WithContainerTag>(wrong_tag).expect_err("wrong tag should fail");
+    assert!(matches!(err, cbor::DecodeError::UnexpectedTag));
+
+    // A value with no tag at all is rejected too.
+    let untagged = Value::Map(vec![
+        (Value::TextString("foo".to_owned()), Value::Unsigned(1)),
+        (
+            Value::TextString("bar".to_owned()),
+            Value::TextString("hi".to_owned()),
+        ),
+    ]);
+    let err = cbor::from_value::<WithContainerTag>(untagged).expect_err("untagged should fail");
+    assert!(matches!(err, cbor::DecodeError::UnexpectedType));
+}
+
+#[test]
+fn test_with_field_tag() {
+    use cbor::Value;
+
+    let value = WithFieldTag {
+        foo: 1,
+        bar: "hi".to_owned(),
+    };
+    let enc = cbor::to_value(value.clone());
+    let bar_value = match &enc {
+        Value::Map(items) => items
+            .iter()
+            .find(|(k, _)| *k == Value::TextString("bar".to_owned()))
+            .map(|(_, v)| v.clone())
+            .expect("bar key present"),
+        _ => panic!("expected a map"),
+    };
+    assert!(matches!(bar_value, Value::Tag(32, _)));
+
+    let dec: WithFieldTag = cbor::from_value(enc).expect("field-tagged value should decode");
+    assert_eq!(dec, value);
+
+    // A field whose tag number doesn't match is rejected.
+    let wrong_tag = Value::Map(vec![
+        (
+            Value::TextString("bar".to_owned()),
+            Value::Tag(33, Box::new(Value::TextString("hi".to_owned()))),
+        ),
+        (Value::TextString("foo".to_owned()), Value::Unsigned(1)),
+    ]);
+    let err = cbor::from_value::<WithFieldTag>(wrong_tag).expect_err("wrong tag should fail");
+    assert!(matches!(err, cbor::DecodeError::UnexpectedTag));
+}
+
+#[test]
+fn test_with_flatten_in_enum_variant() {
+    use cbor::Value;
+
+    // A shared "header" struct flattened into a struct-like enum variant, composing it the same
+    // way a wire protocol message would.
+    let value = MessageWithFlattenedHeader::Ping {
+        header: Header {
+            version: 1,
+            name: "node-a".to_owned(),
+        },
+        nonce: 42,
+    };
+    let enc = cbor::to_value(value.clone());
+    assert_eq!(
+        enc,
+        Value::Map(vec![(
+            Value::TextString("ping".to_owned()),
+            Value::Map(vec![
+                (
+                    Value::TextString("name".to_owned()),
+                    Value::TextString("node-a".to_owned())
+                ),
+                (Value::TextString("nonce".to_owned()), Value::Unsigned(42)),
+                (Value::TextString("version".to_owned()), Value::Unsigned(1)),
+            ]),
+        )]),
+        "flatten's merged entries should already be in canonical order, even inside an enum variant"
+    );
+
+    // Flatten's own round trip must work directly on its own encode output, not just on a
+    // hand-sorted stand-in for it.
+    let dec: MessageWithFlattenedHeader =
+        cbor::from_value(enc).expect("flattened variant fields should decode");
+    assert_eq!(dec, value);
+}
+
+#[test]
+fn test_deterministic_container() {
+    use cbor::Value;
+
+    // Declaration order ("second", "first", "thirdd") differs from the sorted-by-encoded-key-
+    // bytes order ("first", "second", "thirdd"); #[cbor(deterministic)] should produce the latter
+    // even from plain `to_value`/`to_vec`, without the caller reaching for `to_value_canonical`.
+    let value = DeterministicOrder {
+        second: 2,
+        first: 1,
+        thirdd: 3,
+    };
+    let enc = cbor::to_value(value.clone());
+    let keys: Vec<Value> = match &enc {
+        Value::Map(items) => items.iter().map(|(k, _)| k.clone()).collect(),
+        _ => panic!("expected a map"),
+    };
+    assert_eq!(
+        keys,
+        vec![
+            Value::TextString("first".to_owned()),
+            Value::TextString("second".to_owned()),
+            Value::TextString("thirdd".to_owned()),
+        ],
+        "deterministic encoding should sort by encoded key bytes, not declaration order"
+    );
+
+    let dec: DeterministicOrder = cbor::from_value(enc).expect("round-trip should succeed");
+    assert_eq!(dec, value);
+}
+
 #[test]
 fn test_with_optional() {
     // Optional unit struct is not encoded.
@@ -816,7 +1332,7 @@ fn test_with_optional() {
 #[test]
 fn test_enum_untagged() {
     let untagged = Untagged::First { a: 10, b: 11 };
-    let enc = cbor::to_vec(untagged);
+    let enc = cbor::to_vec(untagged.clone());
     assert_eq!(
         enc,
         vec![
@@ -830,6 +1346,28 @@ fn test_enum_untagged() {
             0x0B, // unsigned(11)
         ]
     );
+    let dec: Untagged = cbor::from_slice(&enc).expect("serialization should round-trip");
+    assert_eq!(dec, untagged, "serialization should round-trip");
+
+    let second = Untagged::Second(7);
+    let enc = cbor::to_vec(second.clone());
+    assert_eq!(enc, vec![0x07], "newtype variants should encode as the bare inner value");
+    let dec: Untagged = cbor::from_slice(&enc).expect("serialization should round-trip");
+    assert_eq!(dec, second, "serialization should round-trip");
+
+    let third = Untagged::Third;
+    let enc = cbor::to_vec(third.clone());
+    assert_eq!(
+        enc,
+        vec![0x65, 0x54, 0x68, 0x69, 0x72, 0x64], // text(5) "Third"
+        "unit variants should encode as the bare key"
+    );
+    let dec: Untagged = cbor::from_slice(&enc).expect("serialization should round-trip");
+    assert_eq!(dec, third, "serialization should round-trip");
+
+    // A value that matches no variant should fail with UnexpectedType, not panic.
+    let err = cbor::from_slice::<Untagged>(&[0xF5]).expect_err("no variant should match `true`");
+    assert!(matches!(err, cbor::DecodeError::UnexpectedType));
 }
 
 #[test]
@@ -879,6 +1417,152 @@ fn test_enum_internally_tagged() {
         .expect_err("missing tag deserialization without any missing variant should fail");
 }
 
+#[test]
+fn test_enum_adjacently_tagged() {
+    let v0 = AdjacentlyTagged::V0;
+    let enc = cbor::to_vec(v0.clone());
+    assert_eq!(
+        enc,
+        vec![
+            // {"c": null, "t": 0}
+            0xA2, // map(2)
+            0x61, 0x63, // "c"
+            0xF6, // null
+            0x61, 0x74, // "t"
+            0x00, // unsigned(0)
+        ],
+        "should encode as expected"
+    );
+    let dec: AdjacentlyTagged = cbor::from_slice(&enc).expect("serialization should round-trip");
+    assert_eq!(dec, v0, "serialization should round-trip");
+
+    let v1 = AdjacentlyTagged::V1 { bar: 42 };
+    let enc = cbor::to_vec(v1.clone());
+    assert_eq!(
+        enc,
+        vec![
+            // {"c": {"bar": 42}, "t": 1}
+            0xA2, // map(2)
+            0x61, 0x63, // "c"
+            0xA1, // map(1)
+            0x63, 0x62, 0x61, 0x72, // "bar"
+            0x18, 0x2A, // unsigned(42)
+            0x61, 0x74, // "t"
+            0x01, // unsigned(1)
+        ],
+        "the variant's body should be its own map entry, not merged with the tag"
+    );
+    let dec: AdjacentlyTagged = cbor::from_slice(&enc).expect("serialization should round-trip");
+    assert_eq!(dec, v1, "serialization should round-trip");
+
+    let v2 = AdjacentlyTagged::V2(Order {
+        second: 2,
+        first: 1,
+        thirdd: 3,
+    });
+    let enc = cbor::to_vec(v2.clone());
+    let dec: AdjacentlyTagged = cbor::from_slice(&enc).expect("serialization should round-trip");
+    assert_eq!(dec, v2, "serialization should round-trip");
+
+    // An unrelated key should be rejected.
+    let bad = vec![
+        0xA2, // map(2)
+        0x61, 0x74, // "t"
+        0x00, // unsigned(0)
+        0x61, 0x78, // "x"
+        0x00, // unsigned(0)
+    ];
+    let err = cbor::from_slice::<AdjacentlyTagged>(&bad).expect_err("unknown key should be rejected");
+    assert!(matches!(err, cbor::DecodeError::UnknownField));
+}
+
+#[test]
+fn test_rename_all_fields() {
+    let v = RenameAllFields {
+        foo_bar: 42,
+        baz_qux: true,
+    };
+    let enc = cbor::to_vec(v.clone());
+    assert_eq!(
+        enc,
+        vec![
+            // {"explicit": true, "fooBar": 42}
+            0xA2, // map(2)
+            0x68, // text(8)
+            0x65, 0x78, 0x70, 0x6C, 0x69, 0x63, 0x69, 0x74, // "explicit"
+            0xF5, // true
+            0x66, // text(6)
+            0x66, 0x6F, 0x6F, 0x42, 0x61, 0x72, // "fooBar"
+            0x18, 0x2A, // unsigned(42)
+        ],
+        "fields without an explicit rename should be converted to camelCase"
+    );
+
+    let dec: RenameAllFields = cbor::from_slice(&enc).expect("serialization should round-trip");
+    assert_eq!(dec, v, "serialization should round-trip");
+}
+
+#[test]
+fn test_rename_all_leaves_integer_keys_untouched() {
+    use cbor::Value;
+
+    // rename_all is a string-casing transform; an integer key set via an explicit rename must
+    // pass through unaffected, while an un-renamed field still gets the container's case applied.
+    let v = RenameAllWithIntegerKey {
+        foo_bar: 1,
+        baz_qux: true,
+    };
+    let enc = cbor::to_value(v.clone());
+    let keys: Vec<Value> = match &enc {
+        Value::Map(items) => items.iter().map(|(k, _)| k.clone()).collect(),
+        _ => panic!("expected a map"),
+    };
+    assert!(keys.contains(&Value::Unsigned(7)));
+    assert!(keys.contains(&Value::TextString("bazQux".to_owned())));
+
+    let dec: RenameAllWithIntegerKey = cbor::from_value(enc).expect("round-trip should succeed");
+    assert_eq!(dec, v);
+}
+
+#[test]
+fn test_rename_all_variants() {
+    let first = RenameAllVariants::FirstVariant;
+    let enc = cbor::to_vec(first.clone());
+    assert_eq!(
+        enc,
+        vec![
+            // "FIRST_VARIANT"
+            0x6D, // text(13)
+            0x46, 0x49, 0x52, 0x53, 0x54, 0x5F, 0x56, 0x41, 0x52, 0x49, 0x41, 0x4E, 0x54,
+        ],
+        "unit variants should be converted to SCREAMING_SNAKE_CASE"
+    );
+    let dec: RenameAllVariants =
+        cbor::from_slice(&enc).expect("serialization should round-trip");
+    assert_eq!(dec, first, "serialization should round-trip");
+
+    let second = RenameAllVariants::SecondVariant { inner_value: 7 };
+    let enc = cbor::to_vec(second.clone());
+    assert_eq!(
+        enc,
+        vec![
+            // {"SECOND_VARIANT": {"innerValue": 7}}
+            0xA1, // map(1)
+            0x6E, // text(14)
+            0x53, 0x45, 0x43, 0x4F, 0x4E, 0x44, 0x5F, 0x56, 0x41, 0x52, 0x49, 0x41, 0x4E,
+            0x54, // "SECOND_VARIANT"
+            0xA1, // map(1)
+            0x6A, // text(10)
+            0x69, 0x6E, 0x6E, 0x65, 0x72, 0x56, 0x61, 0x6C, 0x75, 0x65, // "innerValue"
+            0x07, // unsigned(7)
+        ],
+        "the variant's own rename_all should apply to its fields, independent of the container's"
+    );
+    let dec: RenameAllVariants =
+        cbor::from_slice(&enc).expect("serialization should round-trip");
+    assert_eq!(dec, second, "serialization should round-trip");
+}
+
 #[test]
 fn test_btree_map() {
     let mut map = BTreeMap::new();
@@ -921,6 +1605,189 @@ fn test_hash_map() {
     );
 }
 
+#[test]
+fn test_from_value_with_limits_depth() {
+    // [[[1]]] nests three levels deep.
+    let nested = cbor::Value::Array(vec![cbor::Value::Array(vec![cbor::Value::Array(vec![
+        cbor::Value::Unsigned(1),
+    ])])]);
+
+    let ok: Vec<Vec<Vec<u64>>> =
+        cbor::from_value_with_limits(nested.clone(), cbor::Limits { max_depth: 3 })
+            .expect("decoding within the depth limit should succeed");
+    assert_eq!(ok, vec![vec![vec![1]]]);
+
+    let err = cbor::from_value_with_limits::<Vec<Vec<Vec<u64>>>>(
+        nested,
+        cbor::Limits { max_depth: 1 },
+    )
+    .expect_err("decoding past the depth limit should fail");
+    assert!(matches!(err, cbor::DecodeError::DepthLimitExceeded));
+}
+
+#[test]
+fn test_duplicate_map_key_rejected() {
+    let enc = vec![
+        // {"a": 10, "a": 11}
+        0xA2, // map(2)
+        0x61, // text(1)
+        0x61, // "a"
+        0x0A, // unsigned(10)
+        0x61, // text(1)
+        0x61, // "a"
+        0x0B, // unsigned(11)
+    ];
+    let err = cbor::from_slice::<BTreeMap<String, u64>>(&enc).expect_err("duplicate key");
+    assert!(matches!(err, cbor::DecodeError::DuplicateMapKey));
+
+    let err = cbor::from_slice::<HashMap<String, u64>>(&enc).expect_err("duplicate key");
+    assert!(matches!(err, cbor::DecodeError::DuplicateMapKey));
+}
+
+#[test]
+fn test_canonical_map_key_ordering() {
+    // A `HashMap` gives no guarantee about iteration (and therefore encoding) order, but
+    // `to_vec_canonical` must always sort entries by their encoded key bytes regardless.
+    let mut map = HashMap::new();
+    map.insert("b", 11);
+    map.insert("a", 10);
+    let enc = cbor::to_vec_canonical(map);
+    assert_eq!(
+        enc,
+        vec![
+            // {"a": 10, "b": 11}
+            0xA2, // map(2)
+            0x61, // text(1)
+            0x61, // "a"
+            0x0A, // unsigned(10)
+            0x61, // text(1)
+            0x62, // "b"
+            0x0B, // unsigned(11)
+        ]
+    );
+
+    // Nested maps are canonicalized too.
+    let mut outer = BTreeMap::new();
+    let mut inner = HashMap::new();
+    inner.insert(2u64, "y");
+    inner.insert(1u64, "x");
+    outer.insert("z", inner);
+    let enc = cbor::to_vec_canonical(outer);
+    assert_eq!(
+        enc,
+        vec![
+            0xA1, // map(1)
+            0x61, 0x7A, // "z"
+            0xA2, // map(2)
+            0x01, 0x61, 0x78, // 1: "x"
+            0x02, 0x61, 0x79, // 2: "y"
+        ]
+    );
+}
+
+#[test]
+fn test_from_slice_canonical() {
+    // {"a": 10, "b": 11} -- already in canonical order, so this should decode fine.
+    let sorted = vec![
+        0xA2, // map(2)
+        0x61, 0x61, // "a"
+        0x0A, // unsigned(10)
+        0x61, 0x62, // "b"
+        0x0B, // unsigned(11)
+    ];
+    let dec: BTreeMap<String, u64> =
+        cbor::from_slice_canonical(&sorted).expect("already-canonical input should decode");
+    assert_eq!(dec.get("a"), Some(&10));
+    assert_eq!(dec.get("b"), Some(&11));
+
+    // {"b": 11, "a": 10} -- same logical map, but not sorted by encoded key bytes.
+    let unsorted = vec![
+        0xA2, // map(2)
+        0x61, 0x62, // "b"
+        0x0B, // unsigned(11)
+        0x61, 0x61, // "a"
+        0x0A, // unsigned(10)
+    ];
+    assert!(
+        cbor::from_slice_non_strict::<BTreeMap<String, u64>>(&unsorted).is_ok(),
+        "non-strict from_slice accepts any order"
+    );
+    let err = cbor::from_slice_canonical::<BTreeMap<String, u64>>(&unsorted)
+        .expect_err("non-canonical order should be rejected");
+    assert!(matches!(err, cbor::DecodeError::MapKeyOrdering));
+
+    // {"a": 10, "a": 12} -- same key twice, adjacent in (otherwise ascending) order.
+    let duplicate = vec![
+        0xA2, // map(2)
+        0x61, 0x61, // "a"
+        0x0A, // unsigned(10)
+        0x61, 0x61, // "a"
+        0x0C, // unsigned(12)
+    ];
+    let err = cbor::from_slice_canonical::<BTreeMap<String, u64>>(&duplicate)
+        .expect_err("duplicate key should be rejected");
+    assert!(matches!(err, cbor::DecodeError::DuplicateMapKey));
+
+    // Nested maps are checked too.
+    let nested_unsorted = vec![
+        0xA1, // map(1)
+        0x61, 0x7A, // "z"
+        0xA2, // map(2)
+        0x02, 0x61, 0x79, // 2: "y"
+        0x01, 0x61, 0x78, // 1: "x"
+    ];
+    let err =
+        cbor::from_slice_canonical::<BTreeMap<String, BTreeMap<u64, String>>>(&nested_unsorted)
+            .expect_err("non-canonical nested map should be rejected");
+    assert!(matches!(err, cbor::DecodeError::MapKeyOrdering));
+}
+
+/// A toy interning table: decoding an `InternedString` looks the decoded text up in (or adds it
+/// to) the table and stores only its index, instead of the text itself.
+struct InternTable(Vec<String>);
+
+struct InternedString(usize);
+
+impl cbor::DecodeWithContext<InternTable> for InternedString {
+    fn try_from_cbor_value_with(
+        value: cbor::Value,
+        ctx: &mut InternTable,
+    ) -> Result<Self, cbor::DecodeError> {
+        let s = <String as cbor::Decode>::try_from_cbor_value(value)?;
+        let idx = match ctx.0.iter().position(|existing| existing == &s) {
+            Some(idx) => idx,
+            None => {
+                ctx.0.push(s);
+                ctx.0.len() - 1
+            }
+        };
+        Ok(InternedString(idx))
+    }
+}
+
+#[test]
+fn test_decode_with_context() {
+    let mut ctx = InternTable(vec![]);
+
+    let v: InternedString =
+        cbor::from_slice_with(&cbor::to_vec("hello".to_string()), &mut ctx).unwrap();
+    assert_eq!(v.0, 0);
+    assert_eq!(ctx.0, vec!["hello".to_string()]);
+
+    let v: InternedString =
+        cbor::from_slice_with(&cbor::to_vec("world".to_string()), &mut ctx).unwrap();
+    assert_eq!(v.0, 1);
+
+    let v: InternedString =
+        cbor::from_slice_with(&cbor::to_vec("hello".to_string()), &mut ctx).unwrap();
+    assert_eq!(v.0, 0);
+    assert_eq!(ctx.0, vec!["hello".to_string(), "world".to_string()]);
+
+    // Plain `Decode`/`Encode` types keep working unchanged through the blanket impl.
+    let n: u64 = cbor::from_slice_with(&cbor::to_vec_with(42u64, &mut ctx), &mut ctx).unwrap();
+    assert_eq!(n, 42);
+}
+
 #[test]
 fn test_as_array() {
     let asa = AsArray {
@@ -968,6 +1835,55 @@ fn test_tuples() {
     assert_eq!(dec, t1, "serialization should round-trip");
 }
 
+#[test]
+fn test_smart_pointers_and_containers() {
+    let boxed: Box<u64> = Box::new(42);
+    assert_eq!(cbor::to_vec(boxed), cbor::to_vec(42u64));
+    let dec: Box<u64> = cbor::from_slice(&cbor::to_vec(42u64)).unwrap();
+    assert_eq!(*dec, 42);
+
+    let rc: Rc<String> = Rc::new("hello".to_owned());
+    assert_eq!(cbor::to_vec(rc), cbor::to_vec("hello".to_owned()));
+    let dec: Rc<String> = cbor::from_slice(&cbor::to_vec("hello".to_owned())).unwrap();
+    assert_eq!(*dec, "hello");
+
+    let arc: Arc<u64> = Arc::new(7);
+    assert_eq!(cbor::to_vec(arc), cbor::to_vec(7u64));
+    let dec: Arc<u64> = cbor::from_slice(&cbor::to_vec(7u64)).unwrap();
+    assert_eq!(*dec, 7);
+
+    let cow: Cow<'_, u64> = Cow::Owned(9);
+    assert_eq!(cbor::to_vec(cow), cbor::to_vec(9u64));
+    let dec: Cow<'static, u64> = cbor::from_slice(&cbor::to_vec(9u64)).unwrap();
+    assert_eq!(*dec, 9);
+
+    let deque: VecDeque<u64> = VecDeque::from(vec![1, 2, 3]);
+    let enc = cbor::to_vec(deque);
+    assert_eq!(enc, cbor::to_vec(vec![1u64, 2, 3]));
+    let dec: VecDeque<u64> = cbor::from_slice(&enc).unwrap();
+    assert_eq!(dec, VecDeque::from(vec![1, 2, 3]));
+
+    let list: LinkedList<u64> = LinkedList::from_iter([1, 2, 3]);
+    let enc = cbor::to_vec(list);
+    assert_eq!(enc, cbor::to_vec(vec![1u64, 2, 3]));
+    let dec: LinkedList<u64> = cbor::from_slice(&enc).unwrap();
+    assert_eq!(dec, LinkedList::from_iter([1, 2, 3]));
+
+    let heap: BinaryHeap<u64> = BinaryHeap::from(vec![3, 1, 2]);
+    let enc = cbor::to_vec(heap);
+    let dec: BinaryHeap<u64> = cbor::from_slice(&enc).unwrap();
+    assert_eq!(dec.into_sorted_vec(), vec![1, 2, 3]);
+
+    let nz = NonZeroU32::new(5).unwrap();
+    let enc = cbor::to_vec(nz);
+    assert_eq!(enc, cbor::to_vec(5u32));
+    let dec: NonZeroU32 = cbor::from_slice(&enc).expect("non-zero should decode");
+    assert_eq!(dec, nz);
+
+    let err = cbor::from_slice::<NonZeroU32>(&cbor::to_vec(0u32)).expect_err("zero is rejected");
+    assert!(matches!(err, cbor::DecodeError::UnexpectedZero));
+}
+
 #[test]
 fn test_non_string_keys() {
     let nsk = NonStringKeys::One(10, 20);
@@ -1213,6 +2129,62 @@ fn test_custom_encode_decode() {
     assert_eq!(dec, ct);
 }
 
+#[test]
+fn test_custom_decode_with() {
+    // Unlike `test_custom_encode_decode`'s round-trip (which only proves the two sides agree
+    // with each other), decode straight from a hand-built `Value` to confirm `deserialize_with`
+    // itself -- not just `serialize_with` -- is actually wired into the generated `Decode` impl.
+    use cbor::Value;
+
+    let value = Value::Map(vec![(
+        Value::TextString("foo".to_owned()),
+        Value::TextString("hand built".to_owned()),
+    )]);
+    let dec: CustomEncodeDecode = cbor::from_value(value).expect("deserialize_with should decode");
+    assert_eq!(
+        dec,
+        CustomEncodeDecode {
+            foo: CustomType("hand built".to_owned())
+        }
+    );
+}
+
+#[test]
+fn test_byte_vec_and_byte_array() {
+    let bv = cbor::bytes::ByteVec(vec![1, 2, 3]);
+    let enc = cbor::to_vec(bv.clone());
+    assert_eq!(enc, vec![0x43, 0x01, 0x02, 0x03]); // bytes(3) 01 02 03
+    let dec: cbor::bytes::ByteVec = cbor::from_slice(&enc).expect("should round-trip");
+    assert_eq!(dec, bv);
+
+    let ba = cbor::bytes::ByteArray([1u8, 2, 3]);
+    let enc = cbor::to_vec(ba);
+    assert_eq!(enc, vec![0x43, 0x01, 0x02, 0x03]);
+    let dec: cbor::bytes::ByteArray<3> = cbor::from_slice(&enc).expect("should round-trip");
+    assert_eq!(dec.0, ba.0);
+}
+
+#[test]
+fn test_with_bytes_attribute() {
+    let w = WithBytesAttribute {
+        foo: 1,
+        raw: vec![9, 9, 9],
+    };
+    let enc = cbor::to_vec(w.clone());
+    assert_eq!(
+        enc,
+        vec![
+            0xA2, // map(2)
+            0x63, 0x66, 0x6F, 0x6F, // "foo"
+            0x01, // unsigned(1)
+            0x63, 0x72, 0x61, 0x77, // "raw"
+            0x43, 0x09, 0x09, 0x09, // bytes(3) 09 09 09
+        ]
+    );
+    let dec: WithBytesAttribute = cbor::from_slice(&enc).expect("should round-trip");
+    assert_eq!(dec, w);
+}
+
 #[test]
 fn test_custom_encode_decode_array() {
     let ct = CustomEncodeDecodeArray {